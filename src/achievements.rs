@@ -0,0 +1,449 @@
+//! Badges. Each badge is a rule stored as plain data, so teachers can add
+//! their own next to the built-in ones; unlocks and the practice calendar
+//! are kept per student.
+
+use serde::{Deserialize, Serialize};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::mastery::{self, MasteryStats};
+use crate::storage::{app_key, load_json, save_json, student_key};
+use crate::timing::SLOW_MS;
+use crate::{difficulty_label, BaseOp, Difficulty, Fact, Question};
+
+/// Accuracy a fact needs before its times table can count as mastered
+const MASTERED_ACCURACY: f64 = 0.9;
+
+/// Highest factor in a times table
+const TABLE_MAX: i32 = 12;
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Rule {
+    /// `n` first-try correct answers in a row (carries over between quizzes)
+    Streak { n: u32 },
+    /// Every question right on the first try; `None` = at any difficulty
+    PerfectScore { difficulty: Option<Difficulty> },
+    /// Practiced on `days` days in a row, up to today
+    DailyStreak { days: u32 },
+    /// Every fact of a times table accurate and quick; `None` = any of 2–12
+    TableMastered { table: Option<i32> },
+    /// Total graded quizzes
+    QuizzesCompleted { n: u32 },
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Badge {
+    pub id: String,
+    pub icon: String,
+    pub name: String,
+    pub rule: Rule,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Unlock {
+    pub id: String,
+    /// Day number (see `leitner::today`) it was earned
+    pub day: i64,
+}
+
+/// Everything the badge rules need to remember about one student
+#[derive(Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Trophies {
+    pub unlocked: Vec<Unlock>,
+    /// Distinct days with at least one graded quiz, ascending
+    pub practice_days: Vec<i64>,
+    pub quizzes: u32,
+    /// First-try correct answers in a row, as of the last quiz
+    pub running_streak: u32,
+}
+
+fn badge(id: &str, icon: &str, name: &str, rule: Rule) -> Badge {
+    Badge {
+        id: id.to_string(),
+        icon: icon.to_string(),
+        name: name.to_string(),
+        rule,
+    }
+}
+
+pub fn builtin_badges() -> Vec<Badge> {
+    vec![
+        badge(
+            "first-quiz",
+            "🎒",
+            "First quiz",
+            Rule::QuizzesCompleted { n: 1 },
+        ),
+        badge("streak-5", "🔥", "5 in a row", Rule::Streak { n: 5 }),
+        badge("streak-10", "🚀", "10 in a row", Rule::Streak { n: 10 }),
+        badge("streak-25", "🌠", "25 in a row", Rule::Streak { n: 25 }),
+        badge(
+            "perfect-easy",
+            "⭐",
+            "No mistakes (Easy)",
+            Rule::PerfectScore {
+                difficulty: Some(Difficulty::Easy),
+            },
+        ),
+        badge(
+            "perfect-moderate",
+            "🌟",
+            "No mistakes (Moderate)",
+            Rule::PerfectScore {
+                difficulty: Some(Difficulty::Moderate),
+            },
+        ),
+        badge(
+            "perfect-advanced",
+            "💎",
+            "No mistakes (Advanced)",
+            Rule::PerfectScore {
+                difficulty: Some(Difficulty::Advanced),
+            },
+        ),
+        badge(
+            "daily-3",
+            "📅",
+            "3 days in a row",
+            Rule::DailyStreak { days: 3 },
+        ),
+        badge(
+            "daily-7",
+            "🗓️",
+            "A whole week",
+            Rule::DailyStreak { days: 7 },
+        ),
+        badge(
+            "first-table",
+            "✖️",
+            "First times table",
+            Rule::TableMastered { table: None },
+        ),
+        badge(
+            "quizzes-25",
+            "🏅",
+            "25 quizzes",
+            Rule::QuizzesCompleted { n: 25 },
+        ),
+    ]
+}
+
+/// Badges teachers added on this device
+pub fn load_custom_badges() -> Vec<Badge> {
+    load_json(&app_key("custom-badges")).unwrap_or_default()
+}
+
+pub fn save_custom_badges(badges: &[Badge]) {
+    save_json(&app_key("custom-badges"), &badges);
+}
+
+pub fn load_trophies(student: &str) -> Trophies {
+    load_json(&student_key("trophies", student)).unwrap_or_default()
+}
+
+pub fn save_trophies(student: &str, trophies: &Trophies) {
+    save_json(&student_key("trophies", student), trophies);
+}
+
+/// What a rule asks for, in words a kid can read
+pub fn rule_text(rule: &Rule) -> String {
+    match rule {
+        Rule::Streak { n } => format!("Get {n} answers right in a row on the first try"),
+        Rule::PerfectScore {
+            difficulty: Some(d),
+        } => {
+            format!("Finish a {} quiz with no mistakes", difficulty_label(d))
+        }
+        Rule::PerfectScore { difficulty: None } => "Finish any quiz with no mistakes".to_string(),
+        Rule::DailyStreak { days } => format!("Practice {days} days in a row"),
+        Rule::TableMastered { table: Some(t) } => format!("Know the whole {t} times table"),
+        Rule::TableMastered { table: None } => "Know a whole times table (2–12)".to_string(),
+        Rule::QuizzesCompleted { n } => format!("Finish {n} quizzes"),
+    }
+}
+
+/// Practice days in a row ending today (0 if not today)
+fn day_streak(days: &[i64], today: i64) -> u32 {
+    let mut streak = 0;
+    let mut day = today;
+    while days.binary_search(&day).is_ok() {
+        streak += 1;
+        day -= 1;
+    }
+    streak
+}
+
+/// Known = tried, mostly right and not slow, in either order
+fn fact_known(stats: &MasteryStats, a: i32, b: i32) -> bool {
+    [
+        Fact {
+            op: BaseOp::Mul,
+            a,
+            b,
+        },
+        Fact {
+            op: BaseOp::Mul,
+            a: b,
+            b: a,
+        },
+    ]
+    .iter()
+    .filter_map(|f| mastery::find(stats, f))
+    .any(|s| {
+        s.attempts > 0
+            && mastery::accuracy(s) >= MASTERED_ACCURACY
+            && mastery::avg_ms(s).is_none_or(|ms| ms <= SLOW_MS)
+    })
+}
+
+fn table_mastered(stats: &MasteryStats, table: i32) -> bool {
+    (0..=TABLE_MAX).all(|b| fact_known(stats, table, b))
+}
+
+/// Fold one graded quiz into `trophies` and return the badges it unlocked
+pub fn record_quiz(
+    trophies: &mut Trophies,
+    badges: &[Badge],
+    questions: &[Question],
+    difficulty: &Difficulty,
+    mastery: &MasteryStats,
+    today: i64,
+) -> Vec<Badge> {
+    if questions.is_empty() {
+        return Vec::new();
+    }
+    trophies.quizzes += 1;
+    if let Err(pos) = trophies.practice_days.binary_search(&today) {
+        trophies.practice_days.insert(pos, today);
+    }
+
+    let mut best_streak = trophies.running_streak;
+    for q in questions {
+        if q.first_try_correct == Some(true) {
+            trophies.running_streak += 1;
+            best_streak = best_streak.max(trophies.running_streak);
+        } else {
+            trophies.running_streak = 0;
+        }
+    }
+    let perfect = questions.iter().all(|q| q.first_try_correct == Some(true));
+    let days = day_streak(&trophies.practice_days, today);
+
+    let mut earned = Vec::new();
+    for badge in badges {
+        if trophies.unlocked.iter().any(|u| u.id == badge.id) {
+            continue;
+        }
+        let met = match &badge.rule {
+            Rule::Streak { n } => best_streak >= *n,
+            Rule::PerfectScore { difficulty: want } => {
+                perfect && want.as_ref().is_none_or(|d| d == difficulty)
+            }
+            Rule::DailyStreak { days: want } => days >= *want,
+            Rule::TableMastered { table: Some(t) } => table_mastered(mastery, *t),
+            Rule::TableMastered { table: None } => {
+                (2..=TABLE_MAX).any(|t| table_mastered(mastery, t))
+            }
+            Rule::QuizzesCompleted { n } => trophies.quizzes >= *n,
+        };
+        if met {
+            trophies.unlocked.push(Unlock {
+                id: badge.id.clone(),
+                day: today,
+            });
+            earned.push(badge.clone());
+        }
+    }
+    earned
+}
+
+#[derive(Properties, PartialEq)]
+pub struct TrophyShelfProps {
+    pub badges: Vec<Badge>,
+    pub trophies: Trophies,
+    pub today: i64,
+    /// Show the editor for custom badges
+    pub teacher_mode: bool,
+    pub on_add: Callback<Badge>,
+    pub on_remove: Callback<String>,
+}
+
+#[function_component(TrophyShelf)]
+pub fn trophy_shelf(props: &TrophyShelfProps) -> Html {
+    let icon = use_state(|| "🏆".to_string());
+    let name = use_state(String::new);
+    let kind = use_state(|| "streak".to_string());
+    let amount = use_state(|| 15i32);
+    let difficulty = use_state(|| "any".to_string());
+
+    let text_input = |handle: &UseStateHandle<String>| {
+        let handle = handle.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            handle.set(input.value());
+        })
+    };
+    let select_input = |handle: &UseStateHandle<String>| {
+        let handle = handle.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlInputElement = e.target_unchecked_into();
+            handle.set(select.value());
+        })
+    };
+    let on_amount = {
+        let amount = amount.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            amount.set(input.value().parse::<i32>().unwrap_or(1).max(1));
+        })
+    };
+
+    let on_add = {
+        let icon = icon.clone();
+        let name = name.clone();
+        let kind = kind.clone();
+        let amount = amount.clone();
+        let difficulty = difficulty.clone();
+        let existing: Vec<String> = props.badges.iter().map(|b| b.id.clone()).collect();
+        let on_add = props.on_add.clone();
+        Callback::from(move |_| {
+            let title = name.trim().to_string();
+            if title.is_empty() {
+                return;
+            }
+            let n = (*amount).max(1);
+            let rule = match kind.as_str() {
+                "perfect" => Rule::PerfectScore {
+                    difficulty: match difficulty.as_str() {
+                        "easy" => Some(Difficulty::Easy),
+                        "moderate" => Some(Difficulty::Moderate),
+                        "advanced" => Some(Difficulty::Advanced),
+                        _ => None,
+                    },
+                },
+                "daily" => Rule::DailyStreak { days: n as u32 },
+                "table" => Rule::TableMastered {
+                    table: Some(n.min(TABLE_MAX)),
+                },
+                "quizzes" => Rule::QuizzesCompleted { n: n as u32 },
+                _ => Rule::Streak { n: n as u32 },
+            };
+            let base = format!("custom-{}", title.to_lowercase().replace(' ', "-"));
+            let mut id = base.clone();
+            let mut k = 2;
+            while existing.contains(&id) {
+                id = format!("{base}-{k}");
+                k += 1;
+            }
+            on_add.emit(Badge {
+                id,
+                icon: icon.trim().to_string(),
+                name: title,
+                rule,
+            });
+            name.set(String::new());
+        })
+    };
+
+    let earned_count = props
+        .badges
+        .iter()
+        .filter(|b| props.trophies.unlocked.iter().any(|u| u.id == b.id))
+        .count();
+
+    html! {
+        <div>
+            <div class="tiny-note">
+                {format!(
+                    "{} of {} badges · {} quizzes · practice streak: {} day(s) · current run: {} in a row",
+                    earned_count,
+                    props.badges.len(),
+                    props.trophies.quizzes,
+                    day_streak(&props.trophies.practice_days, props.today),
+                    props.trophies.running_streak
+                )}
+            </div>
+            <div class="trophy-shelf">
+                { for props.badges.iter().map(|b| {
+                    let unlock = props.trophies.unlocked.iter().find(|u| u.id == b.id);
+                    let custom = b.id.starts_with("custom-");
+                    let on_remove = props.on_remove.clone();
+                    let id = b.id.clone();
+                    html! {
+                        <div class={classes!("trophy", unlock.is_none().then_some("locked"))}>
+                            <div class="trophy-icon">{b.icon.clone()}</div>
+                            <div class="trophy-name">{b.name.clone()}</div>
+                            <div class="tiny-note">{rule_text(&b.rule)}</div>
+                            if props.teacher_mode && custom {
+                                <button
+                                    class="btn-secondary"
+                                    onclick={Callback::from(move |_| on_remove.emit(id.clone()))}
+                                >
+                                    {"Remove"}
+                                </button>
+                            }
+                        </div>
+                    }
+                }) }
+            </div>
+
+            if props.teacher_mode {
+                <h3>{"Add a badge"}</h3>
+                <div class="config-grid">
+                    <div>
+                        <div class="field-label"><span>{"Icon & name"}</span></div>
+                        <div class="mix-row">
+                            <input
+                                class="mix-input"
+                                type="text"
+                                value={(*icon).clone()}
+                                oninput={text_input(&icon)}
+                            />
+                            <input
+                                class="field-input"
+                                type="text"
+                                placeholder="Badge name"
+                                value={(*name).clone()}
+                                oninput={text_input(&name)}
+                            />
+                        </div>
+                    </div>
+                    <div>
+                        <div class="field-label"><span>{"Earned for"}</span></div>
+                        <select class="field-input" onchange={select_input(&kind)}>
+                            <option value="streak" selected={*kind == "streak"}>{"N right in a row"}</option>
+                            <option value="perfect" selected={*kind == "perfect"}>{"A perfect quiz"}</option>
+                            <option value="daily" selected={*kind == "daily"}>{"N days in a row"}</option>
+                            <option value="table" selected={*kind == "table"}>{"Times table N mastered"}</option>
+                            <option value="quizzes" selected={*kind == "quizzes"}>{"N quizzes finished"}</option>
+                        </select>
+                    </div>
+                    <div>
+                        if *kind == "perfect" {
+                            <div class="field-label"><span>{"Difficulty"}</span></div>
+                            <select class="field-input" onchange={select_input(&difficulty)}>
+                                <option value="any" selected={*difficulty == "any"}>{"Any"}</option>
+                                <option value="easy" selected={*difficulty == "easy"}>{"Easy"}</option>
+                                <option value="moderate" selected={*difficulty == "moderate"}>{"Moderate"}</option>
+                                <option value="advanced" selected={*difficulty == "advanced"}>{"Advanced"}</option>
+                            </select>
+                        } else {
+                            <div class="field-label"><span>{"N"}</span></div>
+                            <input
+                                class="field-input"
+                                type="number"
+                                min="1"
+                                value={amount.to_string()}
+                                oninput={on_amount}
+                            />
+                        }
+                    </div>
+                </div>
+                <div class="btn-row">
+                    <button class="btn-primary" onclick={on_add}>{"Add badge"}</button>
+                </div>
+            }
+        </div>
+    }
+}
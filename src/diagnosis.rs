@@ -0,0 +1,254 @@
+//! Error-pattern diagnosis: guess *why* a basic fact was answered wrong
+//! by checking the answer against common buggy procedures.
+
+use crate::{BaseOp, Fact, Question};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ErrorPattern {
+    /// Subtracted the smaller digit from the larger in each column
+    SmallerFromLarger,
+    /// Added each column but dropped the carries, or wrote every
+    /// column's full sum ("715" for 47 + 38)
+    ForgotToCarry,
+    /// Answer fits another operation on the same numbers
+    WrongOperation,
+    /// Operands lined up in the wrong columns
+    PlaceValue,
+    /// Right digits, wrong order ("71" for 17)
+    ReversedDigits,
+    OffByOne,
+    /// Wrong, but no known pattern fits
+    Other,
+}
+
+pub fn pattern_label(p: ErrorPattern) -> &'static str {
+    match p {
+        ErrorPattern::SmallerFromLarger => "smaller digit from larger",
+        ErrorPattern::ForgotToCarry => "forgot to carry",
+        ErrorPattern::WrongOperation => "used the wrong operation",
+        ErrorPattern::PlaceValue => "place-value misalignment",
+        ErrorPattern::ReversedDigits => "reversed digits",
+        ErrorPattern::OffByOne => "off by one",
+        ErrorPattern::Other => "no clear pattern",
+    }
+}
+
+/// Decimal digits, least significant first
+fn digits(n: i64) -> Vec<i64> {
+    let mut n = n.abs();
+    let mut out = vec![n % 10];
+    n /= 10;
+    while n > 0 {
+        out.push(n % 10);
+        n /= 10;
+    }
+    out
+}
+
+fn from_digits(ds: &[i64]) -> i64 {
+    ds.iter().rev().fold(0, |acc, d| acc * 10 + d)
+}
+
+fn reversed(n: i64) -> i64 {
+    let mut ds = digits(n);
+    ds.reverse();
+    from_digits(&ds)
+}
+
+/// Column-by-column combination with no carrying or borrowing
+fn columnwise(a: i64, b: i64, f: impl Fn(i64, i64) -> i64) -> i64 {
+    from_digits(&columns(a, b, f))
+}
+
+fn columns(a: i64, b: i64, f: impl Fn(i64, i64) -> i64) -> Vec<i64> {
+    let (da, db) = (digits(a), digits(b));
+    let width = da.len().max(db.len());
+    (0..width)
+        .map(|i| f(*da.get(i).unwrap_or(&0), *db.get(i).unwrap_or(&0)))
+        .collect()
+}
+
+/// Each column's sum written out in full, side by side: 47 + 38 → 715
+fn column_sums_written_out(a: i64, b: i64) -> Option<i64> {
+    let sums = columns(a, b, |x, y| x + y);
+    if sums.iter().all(|&s| s < 10) {
+        return None; // nothing to carry, same as the right answer
+    }
+    let text: String = sums.iter().rev().map(|s| s.to_string()).collect();
+    text.parse().ok()
+}
+
+/// Operands widened to i64 so no typed answer or operand product can overflow
+fn operands(fact: &Fact) -> (BaseOp, i64, i64) {
+    (fact.op, fact.a as i64, fact.b as i64)
+}
+
+fn answer(op: BaseOp, a: i64, b: i64) -> Option<i64> {
+    match op {
+        BaseOp::Add => Some(a + b),
+        BaseOp::Sub => Some(a - b),
+        BaseOp::Mul => Some(a * b),
+        BaseOp::Div => a.checked_div(b),
+    }
+}
+
+fn other_op_results(fact: &Fact) -> Vec<i64> {
+    let (op, a, b) = operands(fact);
+    let mut out = Vec::new();
+    if op != BaseOp::Add {
+        out.push(a + b);
+    }
+    if op != BaseOp::Sub {
+        out.push(a - b);
+        out.push(b - a);
+    }
+    if op != BaseOp::Mul {
+        out.push(a * b);
+    }
+    if op != BaseOp::Div && b != 0 && a % b == 0 {
+        out.push(a / b);
+    }
+    out
+}
+
+fn place_value_results(fact: &Fact) -> Vec<i64> {
+    let (op, a, b) = operands(fact);
+    match op {
+        BaseOp::Add => vec![a * 10 + b, a + b * 10],
+        BaseOp::Sub => vec![a * 10 - b, a - b * 10],
+        BaseOp::Mul => {
+            let p = a * b;
+            if p % 10 == 0 {
+                vec![p * 10, p / 10]
+            } else {
+                vec![p * 10]
+            }
+        }
+        BaseOp::Div => a.checked_div(b).map(|q| q * 10).into_iter().collect(),
+    }
+}
+
+/// Classify a wrong numeric answer to a fact. Most specific patterns
+/// are checked first; `None` when the answer is actually right.
+pub fn diagnose(fact: &Fact, given: i32) -> Option<ErrorPattern> {
+    let (op, a, b) = operands(fact);
+    let correct = answer(op, a, b)?;
+    let given = given as i64;
+    if given == correct {
+        return None;
+    }
+
+    let pattern = if op == BaseOp::Sub && given == columnwise(a, b, |x, y| (x - y).abs()) {
+        ErrorPattern::SmallerFromLarger
+    } else if op == BaseOp::Add
+        && (given == columnwise(a, b, |x, y| (x + y) % 10)
+            || Some(given) == column_sums_written_out(a, b))
+    {
+        ErrorPattern::ForgotToCarry
+    } else if other_op_results(fact).contains(&given) {
+        ErrorPattern::WrongOperation
+    } else if place_value_results(fact).contains(&given) {
+        ErrorPattern::PlaceValue
+    } else if correct >= 10 && given == reversed(correct) {
+        ErrorPattern::ReversedDigits
+    } else if given.abs_diff(correct) == 1 {
+        ErrorPattern::OffByOne
+    } else {
+        ErrorPattern::Other
+    };
+    Some(pattern)
+}
+
+/// Diagnosis for a graded basic question answered with a wrong number
+pub fn diagnose_question(q: &Question) -> Option<ErrorPattern> {
+    if q.is_correct != Some(false) {
+        return None;
+    }
+    let fact = q.fact.as_ref()?;
+    let given = q.user_answer.trim().parse::<i32>().ok()?;
+    diagnose(fact, given)
+}
+
+/// Count of each diagnosed pattern across a quiz, most common first.
+/// `Other` is left out: it names no mistake worth pointing at.
+pub fn summarize(qs: &[Question]) -> Vec<(ErrorPattern, usize)> {
+    let mut counts: Vec<(ErrorPattern, usize)> = Vec::new();
+    let found = qs.iter().filter_map(diagnose_question);
+    for p in found.filter(|&p| p != ErrorPattern::Other) {
+        match counts.iter_mut().find(|(q, _)| *q == p) {
+            Some((_, n)) => *n += 1,
+            None => counts.push((p, 1)),
+        }
+    }
+    counts.sort_by(|x, y| y.1.cmp(&x.1).then(x.0.cmp(&y.0)));
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fact(op: BaseOp, a: i32, b: i32) -> Fact {
+        Fact { op, a, b }
+    }
+
+    #[test]
+    fn each_pattern_is_recognised() {
+        let cases = [
+            (
+                fact(BaseOp::Sub, 52, 38),
+                26,
+                ErrorPattern::SmallerFromLarger,
+            ),
+            (fact(BaseOp::Add, 47, 38), 75, ErrorPattern::ForgotToCarry),
+            (fact(BaseOp::Add, 47, 38), 715, ErrorPattern::ForgotToCarry),
+            (fact(BaseOp::Mul, 6, 3), 9, ErrorPattern::WrongOperation),
+            (fact(BaseOp::Add, 23, 4), 234, ErrorPattern::PlaceValue),
+            (fact(BaseOp::Add, 9, 8), 71, ErrorPattern::ReversedDigits),
+            (fact(BaseOp::Mul, 7, 8), 57, ErrorPattern::OffByOne),
+            (fact(BaseOp::Mul, 7, 8), 40, ErrorPattern::Other),
+        ];
+        for (f, given, want) in cases {
+            let got = diagnose(&f, given);
+            assert!(
+                got == Some(want),
+                "{} {} {}: got {:?}, want {}",
+                f.a,
+                given,
+                f.b,
+                got.map(pattern_label),
+                pattern_label(want)
+            );
+        }
+    }
+
+    #[test]
+    fn right_answers_and_bad_facts_have_no_pattern() {
+        assert!(diagnose(&fact(BaseOp::Add, 47, 38), 85).is_none());
+        assert!(diagnose(&fact(BaseOp::Div, 5, 0), 0).is_none());
+    }
+
+    #[test]
+    fn summary_leaves_out_unclear_mistakes() {
+        let wrong = |f: Fact, given: &str| Question {
+            fact: Some(f),
+            user_answer: given.to_string(),
+            is_correct: Some(false),
+            ..Question::default()
+        };
+        let qs = [
+            wrong(fact(BaseOp::Mul, 7, 8), "40"),
+            wrong(fact(BaseOp::Mul, 7, 8), "57"),
+            wrong(fact(BaseOp::Add, 47, 38), "75"),
+            wrong(fact(BaseOp::Add, 9, 8), "16"),
+        ];
+        let summary = summarize(&qs);
+        assert!(
+            summary
+                == [
+                    (ErrorPattern::OffByOne, 2),
+                    (ErrorPattern::ForgotToCarry, 1)
+                ]
+        );
+    }
+}
@@ -0,0 +1,246 @@
+//! CSV export of graded quizzes, laid out for pasting into a gradebook,
+//! and the browser download that delivers it.
+
+use std::time::Duration;
+
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{Blob, BlobPropertyBag, HtmlAnchorElement, Url};
+use yew::platform::time::sleep;
+
+use crate::history::{format_date, History, SessionQuestion};
+use crate::{difficulty_label, log, unanswered_reason, Difficulty, Question, Unanswered};
+
+const QUESTION_COLUMNS: [&str; 7] = [
+    "#",
+    "Kind",
+    "Prompt",
+    "Correct answer",
+    "Student answer",
+    "Result",
+    "Time (s)",
+];
+
+/// One question as it appears in a CSV row
+struct Row<'a> {
+    kind: &'a str,
+    prompt: &'a str,
+    answer: i32,
+    given: &'a str,
+    is_correct: Option<bool>,
+    elapsed_ms: f64,
+}
+
+impl<'a> From<&'a Question> for Row<'a> {
+    fn from(q: &'a Question) -> Self {
+        Row {
+            kind: &q.kind,
+            prompt: &q.prompt,
+            answer: q.answer,
+            given: &q.user_answer,
+            is_correct: q.is_correct,
+            elapsed_ms: q.elapsed_ms,
+        }
+    }
+}
+
+impl<'a> From<&'a SessionQuestion> for Row<'a> {
+    fn from(q: &'a SessionQuestion) -> Self {
+        Row {
+            kind: &q.kind,
+            prompt: &q.prompt,
+            answer: q.answer,
+            given: &q.user_answer,
+            is_correct: q.is_correct,
+            elapsed_ms: q.elapsed_ms,
+        }
+    }
+}
+
+/// A negative number such as "-3" or "-2.5", safe to leave as it is
+fn is_negative_number(value: &str) -> bool {
+    value.strip_prefix('-').is_some_and(|rest| {
+        rest.starts_with(|c: char| c.is_ascii_digit())
+            && rest.chars().all(|c| c.is_ascii_digit() || c == '.')
+            && rest.matches('.').count() <= 1
+    })
+}
+
+/// Quote a field when needed, and defuse text a spreadsheet would run as a formula
+fn field(value: &str) -> String {
+    let value = match value.chars().next() {
+        Some('-') if is_negative_number(value) => value.to_string(),
+        Some('=' | '+' | '-' | '@' | '\t' | '\r') => format!("'{value}"),
+        _ => value.to_string(),
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+fn line(fields: &[String]) -> String {
+    let mut out = fields
+        .iter()
+        .map(|f| field(f))
+        .collect::<Vec<_>>()
+        .join(",");
+    out.push_str("\r\n");
+    out
+}
+
+fn result_label(row: &Row) -> &'static str {
+    match row.is_correct {
+        Some(true) => "correct",
+        Some(false) => "wrong",
+        None => match unanswered_reason(row.given) {
+            Some(Unanswered::Invalid) => "not a number",
+            _ => "blank",
+        },
+    }
+}
+
+fn question_fields(index: usize, row: &Row) -> Vec<String> {
+    vec![
+        (index + 1).to_string(),
+        row.kind.to_string(),
+        row.prompt.to_string(),
+        row.answer.to_string(),
+        row.given.trim().to_string(),
+        result_label(row).to_string(),
+        format!("{:.1}", row.elapsed_ms / 1000.0),
+    ]
+}
+
+/// The quiz on screen: a short summary block, then one row per question
+pub fn quiz_csv(
+    student: &str,
+    date_ms: f64,
+    difficulty: &Difficulty,
+    (correct, total): (usize, usize),
+    questions: &[Question],
+) -> String {
+    let mut out = String::new();
+    out += &line(&["Student".into(), student.to_string()]);
+    out += &line(&["Date".into(), format_date(date_ms)]);
+    out += &line(&[
+        "Difficulty".into(),
+        difficulty_label(difficulty).to_string(),
+    ]);
+    out += &line(&["Score".into(), format!("{correct}/{total}")]);
+    out += "\r\n";
+    out += &line(&QUESTION_COLUMNS.map(String::from));
+    for (i, q) in questions.iter().enumerate() {
+        out += &line(&question_fields(i, &Row::from(q)));
+    }
+    out
+}
+
+/// Every stored session as one flat table, the session summary repeated on each row
+pub fn history_csv(history: &History) -> String {
+    let mut header: Vec<String> = ["Session", "Student", "Date", "Difficulty", "Score"]
+        .map(String::from)
+        .to_vec();
+    header.extend(QUESTION_COLUMNS.map(String::from));
+
+    let mut out = line(&header);
+    for (n, s) in history.sessions.iter().enumerate() {
+        let summary = [
+            (n + 1).to_string(),
+            s.student.clone(),
+            format_date(s.finished_ms),
+            difficulty_label(&s.config.difficulty).to_string(),
+            format!("{}/{}", s.correct, s.total),
+        ];
+        for (i, q) in s.questions.iter().enumerate() {
+            let mut fields = summary.to_vec();
+            fields.extend(question_fields(i, &Row::from(q)));
+            out += &line(&fields);
+        }
+    }
+    out
+}
+
+/// "ana-2026-10-18" + `suffix` (which carries the extension)
+pub fn file_name(student: &str, date_ms: f64, suffix: &str) -> String {
+    let slug: String = student
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect();
+    let slug = if slug.is_empty() {
+        "guest".to_string()
+    } else {
+        slug
+    };
+    format!("{slug}-{}{suffix}", format_date(date_ms))
+}
+
+/// Hand `contents` to the browser as a file download
+pub fn download(file_name: &str, mime: &str, contents: &str) {
+    let result = (|| {
+        let parts = js_sys::Array::of1(&contents.into());
+        let options = BlobPropertyBag::new();
+        options.set_type(mime);
+        let blob = Blob::new_with_str_sequence_and_options(&parts, &options)?;
+        let url = Url::create_object_url_with_blob(&blob)?;
+        let document = web_sys::window()
+            .and_then(|w| w.document())
+            .ok_or("no document")?;
+        let link: HtmlAnchorElement = document.create_element("a")?.unchecked_into();
+        link.set_href(&url);
+        link.set_download(file_name);
+        link.click();
+        // Revoking straight away can cancel the download in some browsers
+        spawn_local(async move {
+            sleep(Duration::from_secs(1)).await;
+            let _ = Url::revoke_object_url(&url);
+        });
+        Ok::<_, wasm_bindgen::JsValue>(())
+    })();
+    if let Err(err) = result {
+        log(&format!("export: download failed: {err:?}"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_values_pass_through() {
+        assert_eq!(field("Ada"), "Ada");
+        assert_eq!(field("42"), "42");
+        assert_eq!(field(""), "");
+    }
+
+    #[test]
+    fn negative_numbers_stay_numbers() {
+        assert_eq!(field("-3"), "-3");
+        assert_eq!(field("-2.5"), "-2.5");
+        assert_eq!(field("-"), "'-");
+        assert_eq!(field("-1+2"), "'-1+2");
+        assert_eq!(field("-.5"), "'-.5");
+        assert_eq!(field("-1.2.3"), "'-1.2.3");
+    }
+
+    #[test]
+    fn formulas_are_defused() {
+        assert_eq!(field("=SUM(A1:A2)"), "'=SUM(A1:A2)");
+        assert_eq!(field("+1"), "'+1");
+        assert_eq!(field("@cmd"), "'@cmd");
+        assert_eq!(field("-A1"), "'-A1");
+        assert_eq!(field("\tx"), "'\tx");
+    }
+
+    #[test]
+    fn separators_and_quotes_are_quoted() {
+        assert_eq!(field("Smith, Ada"), "\"Smith, Ada\"");
+        assert_eq!(field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(field("two\nlines"), "\"two\nlines\"");
+        assert_eq!(field("=1,2"), "\"'=1,2\"");
+        assert_eq!(line(&["a".into(), "b,c".into()]), "a,\"b,c\"\r\n");
+    }
+}
@@ -0,0 +1,224 @@
+//! Step-by-step hints built from a question's structure. Each list goes
+//! from a gentle nudge to a nearly-worked step.
+
+use crate::{BaseOp, Fact, Question};
+
+/// Numbers that appear in a word problem, in order
+fn numbers_in(text: &str) -> Vec<i32> {
+    text.split(|c: char| !c.is_ascii_digit())
+        .filter(|w| !w.is_empty())
+        .filter_map(|w| w.parse().ok())
+        .collect()
+}
+
+/// Words in a story that usually point at an operation
+fn keyword_hint(text: &str) -> Option<&'static str> {
+    let t = text.to_lowercase();
+    let has = |words: &[&str]| words.iter().any(|w| t.contains(w));
+    if has(&["share", "equally", "split", "each get"]) {
+        Some("\"Share equally\" means split into equal groups — that's dividing.")
+    } else if has(&["each", "groups of", "rows of", "times"]) {
+        Some(
+            "\"Each\" or \"groups of\" means the same amount again and again — that's multiplying.",
+        )
+    } else if has(&["left", "fewer", "gave away", "ate", "lost", "how many more"]) {
+        Some("\"Left\", \"gave away\" or \"how many more\" means take away or compare — that's subtracting.")
+    } else if has(&["more", "total", "altogether", "in all", "gets"]) {
+        Some("\"More\", \"in all\" or \"altogether\" means putting amounts together — that's adding.")
+    } else {
+        None
+    }
+}
+
+fn word_hints(prompt: &str) -> Vec<String> {
+    let nums = numbers_in(prompt);
+    let mut hints = Vec::new();
+    match nums.as_slice() {
+        [] => hints.push("Read it again slowly. What is the question asking you to find?".into()),
+        [n] => hints.push(format!(
+            "The important number is {n}. What happens to it in the story?"
+        )),
+        _ => {
+            let list = nums.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+            hints.push(format!(
+                "The key quantities are {}. What does the question ask you to find?",
+                list.join(" and ")
+            ));
+        }
+    }
+    if let Some(k) = keyword_hint(prompt) {
+        hints.push(k.into());
+    }
+    if nums.len() >= 2 {
+        hints.push(format!(
+            "Write a number sentence with {} and {}, then solve it.",
+            nums[0], nums[1]
+        ));
+    }
+    hints
+}
+
+fn add_hints(a: i32, b: i32) -> Vec<String> {
+    let (big, small) = if a >= b { (a, b) } else { (b, a) };
+    if small == 0 {
+        return vec!["Adding 0 changes nothing.".into()];
+    }
+    if big < 10 && small < 10 {
+        if big + small > 10 {
+            let to_ten = 10 - big;
+            vec![
+                "Make a ten first.".into(),
+                format!(
+                    "{big} needs {to_ten} more to make 10. Split {small} into {to_ten} and {}.",
+                    small - to_ten
+                ),
+                format!("{big} + {to_ten} = 10, and 10 + {} = ?", small - to_ten),
+            ]
+        } else {
+            vec![
+                format!("Start at {big} and count up {small}."),
+                format!(
+                    "Count on from {big}: {}…",
+                    (1..=small.min(3))
+                        .map(|i| (big + i).to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            ]
+        }
+    } else {
+        let (a_tens, a_ones) = (a / 10 * 10, a % 10);
+        let (b_tens, b_ones) = (b / 10 * 10, b % 10);
+        let mut hints = vec![
+            "Add the ones first, then the tens (and hundreds).".into(),
+            format!(
+                "Ones: {a_ones} + {b_ones} = {}. Tens and up: {a_tens} + {b_tens} = {}.",
+                a_ones + b_ones,
+                a_tens + b_tens
+            ),
+        ];
+        if a_ones + b_ones >= 10 {
+            hints.push(format!(
+                "The ones make {}, so carry 1 ten over.",
+                a_ones + b_ones
+            ));
+        }
+        hints.push(format!(
+            "Put them together: {} + {} = ?",
+            a_tens + b_tens,
+            a_ones + b_ones
+        ));
+        hints
+    }
+}
+
+fn sub_hints(a: i32, b: i32) -> Vec<String> {
+    if a < 20 {
+        vec![
+            format!("Count up from {b} to {a}. How many steps?"),
+            if a > 10 && b < 10 && a - 10 < b {
+                format!(
+                    "Go back to 10 first: {a} − {} = 10, then take away {} more.",
+                    a - 10,
+                    b - (a - 10)
+                )
+            } else {
+                format!("Think addition: {b} + ? = {a}")
+            },
+        ]
+    } else {
+        let mut hints = vec!["Subtract the ones, then the tens (and hundreds).".into()];
+        if a % 10 < b % 10 {
+            hints.push(format!(
+                "You can't take {} from {} in the ones, so borrow 1 ten: {} ones − {} ones.",
+                b % 10,
+                a % 10,
+                a % 10 + 10,
+                b % 10
+            ));
+        }
+        hints.push(format!("Check with addition: {b} + ? = {a}"));
+        hints
+    }
+}
+
+fn mul_hints(a: i32, b: i32) -> Vec<String> {
+    if a == 0 || b == 0 {
+        return vec!["Anything times 0 is 0.".into()];
+    }
+    let mut hints = vec![format!("{a} × {b} means {b} groups of {a}.")];
+    if b > 5 {
+        hints.push(format!("Think {a} × {b} = {a} × 5 + {a} × {}.", b - 5));
+        hints.push(format!("{a} × 5 = {}. Now add {a} × {}.", a * 5, b - 5));
+    } else if b > 2 {
+        hints.push(format!(
+            "Double it: {a} × 2 = {}, then add the rest.",
+            a * 2
+        ));
+    } else {
+        hints.push(format!("Skip count by {a}, {b} times."));
+    }
+    hints
+}
+
+fn div_hints(a: i32, b: i32) -> Vec<String> {
+    let skip: Vec<String> = (1..=3).map(|i| (b * i).to_string()).collect();
+    vec![
+        format!("How many groups of {b} fit in {a}?"),
+        format!("Think multiplication: {b} × ? = {a}"),
+        format!("Count by {b}s until you reach {a}: {}…", skip.join(", ")),
+    ]
+}
+
+/// All hints for a question, weakest first
+pub fn hints_for(q: &Question) -> Vec<String> {
+    match q.fact {
+        Some(Fact {
+            op: BaseOp::Add,
+            a,
+            b,
+        }) => add_hints(a, b),
+        Some(Fact {
+            op: BaseOp::Sub,
+            a,
+            b,
+        }) => sub_hints(a, b),
+        Some(Fact {
+            op: BaseOp::Mul,
+            a,
+            b,
+        }) => mul_hints(a, b),
+        Some(Fact {
+            op: BaseOp::Div,
+            a,
+            b,
+        }) => div_hints(a, b),
+        None => word_hints(&q.prompt),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hints(op: BaseOp, a: i32, b: i32) -> Vec<String> {
+        hints_for(&Question {
+            fact: Some(Fact { op, a, b }),
+            ..Question::default()
+        })
+    }
+
+    #[test]
+    fn zero_operands_get_plain_hints() {
+        for (a, b) in [(0, 7), (7, 0), (0, 0), (0, 35)] {
+            assert_eq!(hints(BaseOp::Add, a, b), ["Adding 0 changes nothing."]);
+            assert_eq!(hints(BaseOp::Mul, a, b), ["Anything times 0 is 0."]);
+        }
+    }
+
+    #[test]
+    fn make_a_ten_splits_the_smaller_number() {
+        let h = hints(BaseOp::Add, 8, 5);
+        assert_eq!(h[1], "8 needs 2 more to make 10. Split 5 into 2 and 3.");
+    }
+}
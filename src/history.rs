@@ -0,0 +1,350 @@
+//! Every graded quiz, kept per student, and the trend charts drawn from it.
+
+use serde::{Deserialize, Serialize};
+use yew::prelude::*;
+
+use crate::storage::{load_json, save_json, student_key};
+use crate::{
+    difficulty_label, level_tier, op_kind_label, BaseOp, Difficulty, Fact, Question, QuizConfig,
+};
+
+/// Oldest sessions are dropped past this, to stay well inside localStorage
+const MAX_SESSIONS: usize = 500;
+
+const CHART_W: f64 = 600.0;
+const CHART_H: f64 = 220.0;
+const CHART_PAD: f64 = 32.0;
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionQuestion {
+    pub prompt: String,
+    pub kind: String,
+    pub answer: i32,
+    pub user_answer: String,
+    pub is_correct: Option<bool>,
+    pub first_try_correct: Option<bool>,
+    pub fact: Option<Fact>,
+    pub level: u8,
+    pub elapsed_ms: f64,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    pub student: String,
+    /// Epoch milliseconds
+    pub started_ms: f64,
+    pub finished_ms: f64,
+    pub config: QuizConfig,
+    pub questions: Vec<SessionQuestion>,
+    pub correct: usize,
+    pub total: usize,
+}
+
+#[derive(Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct History {
+    pub sessions: Vec<Session>,
+}
+
+pub fn load_history(student: &str) -> History {
+    load_json(&student_key("history", student)).unwrap_or_default()
+}
+
+pub fn save_history(student: &str, history: &History) {
+    save_json(&student_key("history", student), history);
+}
+
+pub fn session_from_quiz(
+    student: &str,
+    config: &QuizConfig,
+    questions: &[Question],
+    (correct, total): (usize, usize),
+    started_ms: f64,
+    finished_ms: f64,
+) -> Session {
+    Session {
+        student: student.trim().to_string(),
+        started_ms,
+        finished_ms,
+        config: config.clone(),
+        questions: questions
+            .iter()
+            .map(|q| SessionQuestion {
+                prompt: q.prompt.clone(),
+                kind: q.kind.clone(),
+                answer: q.answer,
+                user_answer: q.user_answer.clone(),
+                is_correct: q.is_correct,
+                first_try_correct: q.first_try_correct,
+                fact: q.fact,
+                level: q.level,
+                elapsed_ms: q.elapsed_ms,
+            })
+            .collect(),
+        correct,
+        total,
+    }
+}
+
+pub fn add_session(history: &mut History, session: Session) {
+    history.sessions.push(session);
+    if history.sessions.len() > MAX_SESSIONS {
+        let extra = history.sessions.len() - MAX_SESSIONS;
+        history.sessions.drain(..extra);
+    }
+}
+
+/// "2026-10-18" in local time
+pub fn format_date(ms: f64) -> String {
+    let d = js_sys::Date::new(&ms.into());
+    format!(
+        "{}-{:02}-{:02}",
+        d.get_full_year(),
+        d.get_month() + 1,
+        d.get_date()
+    )
+}
+
+/// How the chart splits each session's questions
+#[derive(Clone, Copy, PartialEq)]
+enum Breakdown {
+    Overall,
+    Operation,
+    Difficulty,
+}
+
+/// (label, colour, does this question belong to the series)
+type Series = (&'static str, &'static str, fn(&SessionQuestion) -> bool);
+
+fn is_op(q: &SessionQuestion, op: BaseOp) -> bool {
+    q.fact.is_some_and(|f| f.op == op)
+}
+
+fn is_tier(q: &SessionQuestion, tier: Difficulty) -> bool {
+    level_tier(q.level) == tier
+}
+
+fn series_for(breakdown: Breakdown) -> Vec<Series> {
+    match breakdown {
+        Breakdown::Overall => vec![("All questions", "#2563eb", |_| true)],
+        Breakdown::Operation => vec![
+            (op_kind_label(BaseOp::Add), "#2563eb", |q| {
+                is_op(q, BaseOp::Add)
+            }),
+            (op_kind_label(BaseOp::Sub), "#db2777", |q| {
+                is_op(q, BaseOp::Sub)
+            }),
+            (op_kind_label(BaseOp::Mul), "#16a34a", |q| {
+                is_op(q, BaseOp::Mul)
+            }),
+            (op_kind_label(BaseOp::Div), "#ea580c", |q| {
+                is_op(q, BaseOp::Div)
+            }),
+            ("Word problems", "#7c3aed", |q| q.fact.is_none()),
+        ],
+        Breakdown::Difficulty => vec![
+            (difficulty_label(&Difficulty::Easy), "#16a34a", |q| {
+                is_tier(q, Difficulty::Easy)
+            }),
+            (difficulty_label(&Difficulty::Moderate), "#ea580c", |q| {
+                is_tier(q, Difficulty::Moderate)
+            }),
+            (difficulty_label(&Difficulty::Advanced), "#dc2626", |q| {
+                is_tier(q, Difficulty::Advanced)
+            }),
+        ],
+    }
+}
+
+/// First-try accuracy (0–100) of the matching questions, if there are any
+fn session_accuracy(s: &Session, belongs: fn(&SessionQuestion) -> bool) -> Option<f64> {
+    let picked: Vec<&SessionQuestion> = s.questions.iter().filter(|q| belongs(q)).collect();
+    if picked.is_empty() {
+        return None;
+    }
+    let right = picked
+        .iter()
+        .filter(|q| q.first_try_correct.or(q.is_correct) == Some(true))
+        .count();
+    Some(right as f64 / picked.len() as f64 * 100.0)
+}
+
+fn view_chart(sessions: &[Session], breakdown: Breakdown) -> Html {
+    let first = sessions.first().map(|s| s.finished_ms).unwrap_or(0.0);
+    let last = sessions.last().map(|s| s.finished_ms).unwrap_or(0.0);
+    let span = (last - first).max(1.0);
+    let x_of = |ms: f64| {
+        if sessions.len() < 2 {
+            CHART_W / 2.0
+        } else {
+            CHART_PAD + (ms - first) / span * (CHART_W - 2.0 * CHART_PAD)
+        }
+    };
+    let y_of = |pct: f64| CHART_H - CHART_PAD - pct / 100.0 * (CHART_H - 2.0 * CHART_PAD);
+
+    let grid = [0.0, 50.0, 100.0].map(|pct| {
+        html! {
+            <>
+                <line
+                    x1={CHART_PAD.to_string()}
+                    x2={(CHART_W - CHART_PAD).to_string()}
+                    y1={y_of(pct).to_string()}
+                    y2={y_of(pct).to_string()}
+                    stroke="#e2e8f0"
+                />
+                <text x="2" y={(y_of(pct) + 4.0).to_string()} font-size="10" fill="#64748b">
+                    {format!("{pct}%")}
+                </text>
+            </>
+        }
+    });
+
+    let series = series_for(breakdown);
+    let lines = series.iter().map(|&(label, colour, belongs)| {
+        let points: Vec<(f64, f64, String)> = sessions
+            .iter()
+            .filter_map(|s| {
+                session_accuracy(s, belongs).map(|pct| {
+                    (
+                        x_of(s.finished_ms),
+                        y_of(pct),
+                        format!("{} – {}: {:.0}%", format_date(s.finished_ms), label, pct),
+                    )
+                })
+            })
+            .collect();
+        let path = points
+            .iter()
+            .map(|(x, y, _)| format!("{x:.1},{y:.1}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        html! {
+            <g>
+                <polyline points={path} fill="none" stroke={colour} stroke-width="2" />
+                { for points.into_iter().map(|(x, y, title)| html! {
+                    <circle cx={x.to_string()} cy={y.to_string()} r="3" fill={colour}>
+                        <title>{title}</title>
+                    </circle>
+                }) }
+            </g>
+        }
+    });
+
+    html! {
+        <div>
+            <svg
+                class="history-chart"
+                viewBox={format!("0 0 {CHART_W} {CHART_H}")}
+                role="img"
+                aria-label="Accuracy over time"
+            >
+                { for grid }
+                { for lines }
+                if sessions.len() >= 2 {
+                    <text x={CHART_PAD.to_string()} y={(CHART_H - 8.0).to_string()} font-size="10" fill="#64748b">
+                        {format_date(first)}
+                    </text>
+                    <text
+                        x={(CHART_W - CHART_PAD).to_string()}
+                        y={(CHART_H - 8.0).to_string()}
+                        font-size="10"
+                        fill="#64748b"
+                        text-anchor="end"
+                    >
+                        {format_date(last)}
+                    </text>
+                }
+            </svg>
+            <div class="chart-legend">
+                { for series.iter().map(|&(label, colour, _)| html! {
+                    <span>
+                        <span class="legend-swatch" style={format!("background:{colour}")}></span>
+                        {label}
+                    </span>
+                }) }
+            </div>
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct HistoryViewProps {
+    pub history: History,
+    /// Used to name the exported file
+    pub student: String,
+}
+
+#[function_component(HistoryView)]
+pub fn history_view(props: &HistoryViewProps) -> Html {
+    let breakdown = use_state(|| Breakdown::Operation);
+    let sessions = &props.history.sessions;
+
+    if sessions.is_empty() {
+        return html! {
+            <div class="tiny-note">{"No graded quizzes yet. Finish a quiz and it will show up here."}</div>
+        };
+    }
+
+    let on_export_all = {
+        let history = props.history.clone();
+        let student = props.student.clone();
+        Callback::from(move |_| {
+            let now = js_sys::Date::now();
+            crate::export::download(
+                &crate::export::file_name(&student, now, "-history.csv"),
+                "text/csv",
+                &crate::export::history_csv(&history),
+            );
+        })
+    };
+
+    let pick = |target: Breakdown| {
+        let breakdown = breakdown.clone();
+        Callback::from(move |_| breakdown.set(target))
+    };
+    let tab_class = |target: Breakdown| {
+        if *breakdown == target {
+            "btn-primary"
+        } else {
+            "btn-secondary"
+        }
+    };
+
+    html! {
+        <div>
+            <div class="btn-row">
+                <button class={tab_class(Breakdown::Overall)} onclick={pick(Breakdown::Overall)}>
+                    {"Overall"}
+                </button>
+                <button class={tab_class(Breakdown::Operation)} onclick={pick(Breakdown::Operation)}>
+                    {"By operation"}
+                </button>
+                <button class={tab_class(Breakdown::Difficulty)} onclick={pick(Breakdown::Difficulty)}>
+                    {"By difficulty"}
+                </button>
+                <button class="btn-secondary" onclick={on_export_all}>
+                    {"Export all sessions (CSV)"}
+                </button>
+            </div>
+            <div class="tiny-note">{"First-try accuracy per quiz."}</div>
+            { view_chart(sessions, *breakdown) }
+            <table class="results-table">
+                <tr>
+                    <th>{"Date"}</th>
+                    <th>{"Difficulty"}</th>
+                    <th>{"Questions"}</th>
+                    <th>{"Score"}</th>
+                    <th>{"Minutes"}</th>
+                </tr>
+                { for sessions.iter().rev().map(|s| html! {
+                    <tr>
+                        <td>{format_date(s.finished_ms)}</td>
+                        <td>{difficulty_label(&s.config.difficulty)}</td>
+                        <td>{s.questions.len()}</td>
+                        <td>{format!("{}/{}", s.correct, s.total)}</td>
+                        <td>{format!("{:.1}", (s.finished_ms - s.started_ms).max(0.0) / 60_000.0)}</td>
+                    </tr>
+                }) }
+            </table>
+        </div>
+    }
+}
@@ -0,0 +1,143 @@
+//! Classroom kiosk: one device passed around during center rotations.
+//! Setup and teacher controls are hidden, each student types a first name
+//! before their quiz, the screen resets itself after results, and every
+//! result lands in a class log the teacher exports later.
+
+use std::time::Duration;
+
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::history::History;
+use crate::pin::TeacherLock;
+use crate::storage::{app_key, load_json, save_json};
+
+/// How long results stay up before the next student's turn
+pub const RESET_AFTER: Duration = Duration::from_secs(20);
+
+/// Whether this device is in kiosk mode; kept across reloads so a
+/// refresh doesn't let anyone out
+pub fn load_kiosk() -> bool {
+    load_json(&app_key("kiosk")).unwrap_or(false)
+}
+
+pub fn save_kiosk(on: bool) {
+    save_json(&app_key("kiosk"), &on);
+}
+
+/// Every kiosk result on this device, all students together
+pub fn load_class_log() -> History {
+    load_json(&app_key("class-log")).unwrap_or_default()
+}
+
+pub fn save_class_log(log: &History) {
+    save_json(&app_key("class-log"), log);
+}
+
+#[derive(Properties, PartialEq)]
+pub struct KioskBarProps {
+    /// Whose turn it is; `None` while waiting for a name
+    pub name: Option<AttrValue>,
+    /// Results are on screen
+    pub finished: bool,
+    pub on_start: Callback<String>,
+    pub on_check: Callback<MouseEvent>,
+    pub on_next: Callback<()>,
+    /// Correct PIN entered: leave kiosk mode
+    pub on_exit: Callback<()>,
+}
+
+#[function_component(KioskBar)]
+pub fn kiosk_bar(props: &KioskBarProps) -> Html {
+    let typed = use_state(String::new);
+
+    let on_name = {
+        let typed = typed.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            typed.set(input.value());
+        })
+    };
+
+    let on_submit = {
+        let typed = typed.clone();
+        let on_start = props.on_start.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let name = typed.trim().to_string();
+            if !name.is_empty() {
+                typed.set(String::new());
+                on_start.emit(name);
+            }
+        })
+    };
+
+    let on_next = {
+        let on_next = props.on_next.clone();
+        Callback::from(move |_| on_next.emit(()))
+    };
+
+    let exit = html! {
+        <TeacherLock
+            unlocked_until={None::<f64>}
+            on_unlock={props.on_exit.clone()}
+            on_lock={Callback::noop()}
+            action="Exit kiosk"
+            note=""
+        />
+    };
+
+    let turn = match &props.name {
+        None => html! {
+            <>
+                <h1>{"Math Quest 🎒"}</h1>
+                <form class="kiosk-start" onsubmit={on_submit}>
+                    <div class="field-label"><span>{"What's your first name?"}</span></div>
+                    <input
+                        class="field-input kiosk-name"
+                        type="text"
+                        autocomplete="off"
+                        placeholder="First name"
+                        value={(*typed).clone()}
+                        oninput={on_name}
+                    />
+                    <button class="btn-primary" type="submit" disabled={typed.trim().is_empty()}>
+                        {"Start my quiz"}
+                    </button>
+                </form>
+            </>
+        },
+        Some(name) if props.finished => html! {
+            <>
+                <h2>{format!("Great job, {name}!")}</h2>
+                <div class="tiny-note">
+                    {format!(
+                        "Your score is saved. This screen clears for the next student in {} seconds.",
+                        RESET_AFTER.as_secs()
+                    )}
+                </div>
+                <div class="btn-row">
+                    <button class="btn-primary" onclick={on_next}>{"Next student"}</button>
+                </div>
+            </>
+        },
+        Some(name) => html! {
+            <>
+                <h2>{format!("Hi, {name}! 👋")}</h2>
+                <div class="tiny-note">{"Answer every question below, then check your answers."}</div>
+                <div class="btn-row">
+                    <button class="btn-primary" onclick={props.on_check.clone()}>
+                        {"Check Answers"}
+                    </button>
+                </div>
+            </>
+        },
+    };
+
+    html! {
+        <>
+            {turn}
+            {exit}
+        </>
+    }
+}
@@ -0,0 +1,88 @@
+//! Leitner-box spaced repetition for basic facts.
+//! Every graded fact lands in a box: a miss sends it back to box 1,
+//! a correct answer moves it up one box and pushes its next review out.
+
+use serde::{Deserialize, Serialize};
+
+use crate::storage::{load_json, save_json, student_key};
+use crate::{fact_key, Fact, Question};
+
+pub const BOX_COUNT: u8 = 5;
+
+/// Days until a card in box 1..=5 is due again
+const BOX_INTERVAL_DAYS: [i64; BOX_COUNT as usize] = [0, 1, 3, 7, 14];
+
+const MS_PER_DAY: f64 = 86_400_000.0;
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct LeitnerCard {
+    pub fact: Fact,
+    /// 1 (just missed) ..= BOX_COUNT (well known)
+    pub box_num: u8,
+    /// Day number (days since the Unix epoch) when the card is due
+    pub due_day: i64,
+}
+
+#[derive(Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct LeitnerBoxes {
+    pub cards: Vec<LeitnerCard>,
+}
+
+/// Today's day number in local browser time
+pub fn today() -> i64 {
+    let now = js_sys::Date::new_0();
+    let offset_ms = now.get_timezone_offset() * 60_000.0;
+    ((now.get_time() - offset_ms) / MS_PER_DAY).floor() as i64
+}
+
+pub fn load_boxes(student: &str) -> LeitnerBoxes {
+    load_json(&student_key("leitner", student)).unwrap_or_default()
+}
+
+pub fn save_boxes(student: &str, boxes: &LeitnerBoxes) {
+    save_json(&student_key("leitner", student), boxes);
+}
+
+/// Move a fact between boxes after it was answered
+pub fn record_answer(boxes: &mut LeitnerBoxes, fact: &Fact, correct: bool, today: i64) {
+    let key = fact_key(fact, false);
+    let idx = match boxes.cards.iter().position(|c| fact_key(&c.fact, false) == key) {
+        Some(idx) => idx,
+        None => {
+            boxes.cards.push(LeitnerCard {
+                fact: *fact,
+                box_num: 1,
+                due_day: today,
+            });
+            boxes.cards.len() - 1
+        }
+    };
+    let card = &mut boxes.cards[idx];
+    card.box_num = if correct {
+        (card.box_num + 1).min(BOX_COUNT)
+    } else {
+        1
+    };
+    card.due_day = today + BOX_INTERVAL_DAYS[(card.box_num - 1) as usize];
+}
+
+/// Record every graded basic question in `qs`
+pub fn record_questions(boxes: &mut LeitnerBoxes, qs: &[Question], today: i64) {
+    for q in qs {
+        if let (Some(fact), Some(correct)) = (q.fact.as_ref(), q.is_correct) {
+            record_answer(boxes, fact, correct, today);
+        }
+    }
+}
+
+/// Facts due for review, lowest box (weakest) first
+pub fn due_facts(boxes: &LeitnerBoxes, today: i64) -> Vec<Fact> {
+    let mut due: Vec<&LeitnerCard> = boxes.cards.iter().filter(|c| c.due_day <= today).collect();
+    due.sort_by_key(|c| (c.box_num, c.due_day));
+    due.iter().map(|c| c.fact).collect()
+}
+
+/// Cards in one box, for the teacher view
+pub fn cards_in_box(boxes: &LeitnerBoxes, box_num: u8) -> Vec<&LeitnerCard> {
+    boxes.cards.iter().filter(|c| c.box_num == box_num).collect()
+}
@@ -1,13 +1,43 @@
 #![allow(warnings)]
 
 use js_sys::Math;
-use web_sys::{HtmlInputElement, console};
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlElement, HtmlInputElement, console};
 use yew::prelude::*;
 use yew::TargetCast;
 
 use gloo_net::http::Request;
 use serde::{Deserialize, Serialize};
-use wasm_bindgen_futures::spawn_local;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::Duration;
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use yew::platform::time::sleep;
+
+mod achievements;
+mod diagnosis;
+mod export;
+mod hints;
+mod history;
+mod kiosk;
+mod leitner;
+mod mastery;
+mod pin;
+mod profiles;
+mod qr;
+mod quiz_file;
+mod roster;
+mod share;
+mod solutions;
+mod sprint;
+mod storage;
+mod timing;
+mod versions;
+mod worksheet;
+
+use leitner::LeitnerBoxes;
+use mastery::{MasteryStats, MasteryView};
+use timing::{BrowserClock, Clock};
 
 // Tiny helper to log to browser console
 fn log(msg: &str) {
@@ -17,14 +47,31 @@ fn log(msg: &str) {
 // Your deployed Worker URL
 const AI_WORKER_URL: &str = "https://math-quiz-word-worker.mikegyver.workers.dev/";
 
-#[derive(Clone, PartialEq)]
+/// Prompt of a word problem still waiting for the Worker
+const AI_PLACEHOLDER: &str = "Loading AI word problem...";
+
+/// Allowed quiz length, whether set directly or as the sum of exact counts
+const MIN_QUESTIONS: usize = 5;
+const MAX_QUESTIONS: usize = 20;
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 enum Difficulty {
     Easy,
     Moderate,
     Advanced,
 }
 
-#[derive(Clone, PartialEq)]
+/// How the per-type numbers in the question mix are read
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum MixMode {
+    /// Shares of `num_questions` (normalized over the included types)
+    Percent,
+    /// Exact question counts; the quiz length is their sum
+    Count,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 struct QuizConfig {
     num_questions: usize,
     difficulty: Difficulty,
@@ -33,26 +80,93 @@ struct QuizConfig {
     include_mul: bool,
     include_div: bool,
     include_words: bool,
+    /// Never repeat the same basic fact within one quiz
+    unique_facts: bool,
+    /// Count "3 + 4" and "4 + 3" (or "3 × 4" and "4 × 3") as the same fact
+    commuted_equal: bool,
+    /// Skip zeros, ones and identical operands
+    exclude_trivial: bool,
+    /// Ask one question at a time with instant feedback
+    one_at_a_time: bool,
+    /// Ask one question at a time and move the level with each answer
+    adaptive: bool,
+    /// Start the quiz with facts that are due in the Leitner boxes
+    review_mode: bool,
+    /// Points taken off a correct answer per hint used, in percent
+    hint_penalty_pct: u32,
+    /// Whole-quiz countdown in seconds; 0 means untimed
+    time_limit_secs: u32,
+    mix_mode: MixMode,
+    mix_add: u32,
+    mix_sub: u32,
+    mix_mul: u32,
+    mix_div: u32,
+    mix_words: u32,
+    group_by_type: bool,
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 struct Question {
     prompt: String,
     kind: String,
     answer: i32,
     user_answer: String,
     is_correct: Option<bool>,
+    /// Set instead of `is_correct` when grading found no usable number
+    unanswered: Option<Unanswered>,
+    /// Operands behind a basic question; `None` for word problems
+    fact: Option<Fact>,
+    /// Skill level the question was generated at
+    level: u8,
+    /// Time spent with the answer box focused
+    elapsed_ms: f64,
+    /// When the answer box was last focused, while it still is
+    #[serde(skip)]
+    focus_started: Option<f64>,
+    /// How many hints the student opened
+    hints_used: u8,
+    /// Times this item has been graded with a new answer
+    attempts: u32,
+    /// Result of the very first grading, kept across retry rounds
+    first_try_correct: Option<bool>,
+}
+
+/// Why a graded question has no answer to check
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum Unanswered {
+    /// Left empty
+    Blank,
+    /// Typed something that isn't a whole number, like "1 2"
+    Invalid,
 }
 
-fn default_config() -> QuizConfig {
-    QuizConfig {
-        num_questions: 10,
-        difficulty: Difficulty::Easy,
-        include_add: true,
-        include_sub: true,
-        include_mul: false,
-        include_div: false,
-        include_words: true,
+impl Default for QuizConfig {
+    fn default() -> Self {
+        QuizConfig {
+            num_questions: 10,
+            difficulty: Difficulty::Easy,
+            include_add: true,
+            include_sub: true,
+            include_mul: false,
+            include_div: false,
+            include_words: true,
+            unique_facts: true,
+            commuted_equal: true,
+            exclude_trivial: false,
+            one_at_a_time: false,
+            adaptive: false,
+            review_mode: false,
+            hint_penalty_pct: 25,
+            time_limit_secs: 0,
+            mix_mode: MixMode::Percent,
+            mix_add: 40,
+            mix_sub: 40,
+            mix_mul: 40,
+            mix_div: 40,
+            mix_words: 20,
+            group_by_type: false,
+        }
     }
 }
 
@@ -70,13 +184,57 @@ struct AiWordProblemResponse {
     answer: i32,
 }
 
+thread_local! {
+    /// State of the seeded stream; `None` means use `Math.random`
+    static SEEDED_RNG: RefCell<Option<u64>> = const { RefCell::new(None) };
+}
+
+/// Run `f` with every `rand_int` call drawn from a reproducible stream,
+/// so the same seed always builds the same questions.
+fn with_seed<T>(seed: u64, f: impl FnOnce() -> T) -> T {
+    let outer = SEEDED_RNG.with(|s| s.replace(Some(seed)));
+    let out = f();
+    SEEDED_RNG.with(|s| *s.borrow_mut() = outer);
+    out
+}
+
+/// A fresh seed for `with_seed`
+fn random_seed() -> u64 {
+    let high = (Math::random() * (1u64 << 32) as f64) as u64;
+    let low = (Math::random() * (1u64 << 32) as f64) as u64;
+    high << 32 | low
+}
+
+/// Uniform float in [0, 1): splitmix64 when seeded, `Math.random` otherwise
+fn next_random() -> f64 {
+    SEEDED_RNG.with(|s| match s.borrow_mut().as_mut() {
+        Some(state) => {
+            *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = *state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^= z >> 31;
+            (z >> 11) as f64 / (1u64 << 53) as f64
+        }
+        None => Math::random(),
+    })
+}
+
 /// Random integer in [min, max], inclusive
 fn rand_int(min: i32, max: i32) -> i32 {
-    let r = Math::random();
+    let r = next_random();
     min + ((r * ((max - min + 1) as f64)) as i32)
 }
 
-#[derive(Clone, Copy)]
+/// Fisher–Yates shuffle driven by `rand_int`
+fn shuffle<T>(items: &mut [T]) {
+    for i in (1..items.len()).rev() {
+        let j = rand_int(0, i as i32) as usize;
+        items.swap(i, j);
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
 enum BaseOp {
     Add,
     Sub,
@@ -84,6 +242,13 @@ enum BaseOp {
     Div,
 }
 
+/// One slot in the question mix: a basic fact or an AI word problem
+#[derive(Clone, Copy, PartialEq)]
+enum QuestionType {
+    Basic(BaseOp),
+    Word,
+}
+
 fn difficulty_code(diff: &Difficulty) -> &'static str {
     match diff {
         Difficulty::Easy => "easy",
@@ -100,61 +265,250 @@ fn difficulty_label(diff: &Difficulty) -> &'static str {
     }
 }
 
-/// Return (question text, answer, kind label)
-/// Now with clearer difficulty tiers:
+/// A basic fact `a op b`; for division `a` is the dividend
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct Fact {
+    op: BaseOp,
+    a: i32,
+    b: i32,
+}
+
+/// How many random draws to spend looking for a fresh fact before
+/// walking through the facts that are left
+const MAX_FACT_ATTEMPTS: usize = 500;
+
+fn op_symbol(op: BaseOp) -> &'static str {
+    match op {
+        BaseOp::Add => "+",
+        BaseOp::Sub => "−",
+        BaseOp::Mul => "×",
+        BaseOp::Div => "÷",
+    }
+}
+
+fn op_kind_label(op: BaseOp) -> &'static str {
+    match op {
+        BaseOp::Add => "Addition",
+        BaseOp::Sub => "Subtraction",
+        BaseOp::Mul => "Multiplication",
+        BaseOp::Div => "Division",
+    }
+}
+
+fn op_index(op: BaseOp) -> u8 {
+    match op {
+        BaseOp::Add => 0,
+        BaseOp::Sub => 1,
+        BaseOp::Mul => 2,
+        BaseOp::Div => 3,
+    }
+}
+
+fn fact_answer(fact: &Fact) -> i32 {
+    match fact.op {
+        BaseOp::Add => fact.a + fact.b,
+        BaseOp::Sub => fact.a - fact.b,
+        BaseOp::Mul => fact.a * fact.b,
+        BaseOp::Div => fact.a / fact.b,
+    }
+}
+
+/// Short form of a fact, e.g. "7 × 8"
+fn fact_text(fact: &Fact) -> String {
+    format!("{} {} {}", fact.a, op_symbol(fact.op), fact.b)
+}
+
+/// Return (question text, answer, kind label) for a fact
+fn fact_question_parts(fact: &Fact) -> (String, i32, String) {
+    (
+        format!("{} = ?", fact_text(fact)),
+        fact_answer(fact),
+        op_kind_label(fact.op).into(),
+    )
+}
+
+/// Zeros, ones and identical operands: "7 + 0", "5 × 1", "6 − 6", "8 ÷ 1"
+fn is_trivial_fact(fact: &Fact) -> bool {
+    let Fact { op, a, b } = *fact;
+    match op {
+        BaseOp::Add => a == 0 || b == 0 || a == b,
+        BaseOp::Sub => b == 0 || a == b,
+        BaseOp::Mul => a <= 1 || b <= 1 || a == b,
+        BaseOp::Div => a == 0 || b == 1 || a == b,
+    }
+}
+
+/// Identity used for duplicate checks; commutative ops can fold
+/// "4 + 3" onto "3 + 4"
+fn fact_key(fact: &Fact, commuted_equal: bool) -> (u8, i32, i32) {
+    let op = op_index(fact.op);
+    let commutative = matches!(fact.op, BaseOp::Add | BaseOp::Mul);
+    if commuted_equal && commutative && fact.a > fact.b {
+        (op, fact.b, fact.a)
+    } else {
+        (op, fact.a, fact.b)
+    }
+}
+
+/// Finer-grained skill scale used by adaptive mode. The fixed tiers
+/// sit on it at 2 (Easy), 5 (Moderate) and 8 (Advanced).
+const MIN_LEVEL: u8 = 1;
+const MAX_LEVEL: u8 = 9;
+
+/// Correct answers in a row needed to move up one level
+const ADAPT_UP_STREAK: u32 = 2;
+
+fn tier_level(diff: &Difficulty) -> u8 {
+    match diff {
+        Difficulty::Easy => 2,
+        Difficulty::Moderate => 5,
+        Difficulty::Advanced => 8,
+    }
+}
+
+/// Nearest fixed tier for a level, for labels and word problems
+fn level_tier(level: u8) -> Difficulty {
+    match level {
+        0..=3 => Difficulty::Easy,
+        4..=6 => Difficulty::Moderate,
+        _ => Difficulty::Advanced,
+    }
+}
+
+/// Operand ranges per level:
+/// - + / −: operand (min, max)
+/// - ×: factor (min, max)
+/// - ÷: quotient (min, max), divisor 1..=max
+/// Levels 2/5/8 match the original Easy/Moderate/Advanced tiers:
 /// - Easy: single-digit for + / −, small × / ÷
 /// - Moderate: two-digit for + / −, bigger × / ÷
 /// - Advanced: three-digit for + / −, beefy × / ÷
-fn generate_basic_question(cfg: &QuizConfig, op: BaseOp) -> (String, i32, String) {
+fn level_range(level: u8, op: BaseOp) -> (i32, i32) {
+    let level = level.clamp(MIN_LEVEL, MAX_LEVEL);
     match op {
-        BaseOp::Add => {
-            let (min, max) = match cfg.difficulty {
-                Difficulty::Easy => (0, 9),        // single-digit
-                Difficulty::Moderate => (10, 99),  // two-digit
-                Difficulty::Advanced => (100, 999),// three-digit
-            };
+        BaseOp::Add | BaseOp::Sub => match level {
+            1 => (0, 5),
+            2 => (0, 9),       // single-digit
+            3 => (0, 20),
+            4 => (5, 50),
+            5 => (10, 99),     // two-digit
+            6 => (20, 199),
+            7 => (50, 499),
+            8 => (100, 999),   // three-digit
+            _ => (200, 1999),
+        },
+        BaseOp::Mul => match level {
+            1 => (0, 3),
+            2 => (0, 5),       // times tables 0–5
+            3 => (0, 9),
+            4 => (2, 10),
+            5 => (2, 12),
+            6 => (3, 15),
+            7 => (4, 18),
+            8 => (5, 20),
+            _ => (6, 25),
+        },
+        BaseOp::Div => match level {
+            1 => (1, 5),
+            2 => (1, 9),
+            3 => (1, 10),
+            4 => (2, 10),
+            5 => (2, 12),
+            6 => (3, 15),
+            7 => (4, 18),
+            8 => (5, 20),
+            _ => (6, 25),
+        },
+    }
+}
+
+/// Random fact for an operation at a skill level
+fn random_fact(level: u8, op: BaseOp) -> Fact {
+    let (min, max) = level_range(level, op);
+    match op {
+        BaseOp::Add | BaseOp::Mul => {
             let a = rand_int(min, max);
             let b = rand_int(min, max);
-            (format!("{a} + {b} = ?"), a + b, "Addition".into())
+            Fact { op, a, b }
         }
         BaseOp::Sub => {
-            let (min, max) = match cfg.difficulty {
-                Difficulty::Easy => (0, 9),
-                Difficulty::Moderate => (10, 99),
-                Difficulty::Advanced => (100, 999),
-            };
             let a = rand_int(min, max);
             let b = rand_int(0, a); // ensure non-negative
-            (format!("{a} − {b} = ?"), a - b, "Subtraction".into())
-        }
-        BaseOp::Mul => {
-            // keep multiplication friendly but scaled
-            let (min_f, max_f) = match cfg.difficulty {
-                Difficulty::Easy => (0, 5),   // times tables 0–5
-                Difficulty::Moderate => (2, 12),
-                Difficulty::Advanced => (5, 20),
-            };
-            let a = rand_int(min_f, max_f);
-            let b = rand_int(min_f, max_f);
-            (format!("{a} × {b} = ?"), a * b, "Multiplication".into())
+            Fact { op, a, b }
         }
         BaseOp::Div => {
-            // Whole-number division, scaled by difficulty
-            let (min_q, max_q) = match cfg.difficulty {
-                Difficulty::Easy => (1, 9),
-                Difficulty::Moderate => (2, 12),
-                Difficulty::Advanced => (5, 20),
-            };
-            let divisor = rand_int(1, max_q);
-            let quotient = rand_int(min_q, max_q);
-            let dividend = divisor * quotient;
-            (
-                format!("{dividend} ÷ {divisor} = ?"),
-                quotient,
-                "Division".into(),
-            )
+            // Whole-number division
+            let divisor = rand_int(1, max);
+            let quotient = rand_int(min, max);
+            Fact {
+                op,
+                a: divisor * quotient,
+                b: divisor,
+            }
+        }
+    }
+}
+
+/// Every fact `random_fact` can produce for an operation at a level
+fn possible_facts(level: u8, op: BaseOp) -> impl Iterator<Item = Fact> {
+    let (min, max) = level_range(level, op);
+    let first = match op {
+        BaseOp::Div => 1, // divisor
+        _ => min,
+    };
+    (first..=max).flat_map(move |x| {
+        let (lo, hi) = match op {
+            BaseOp::Sub => (0, x),
+            _ => (min, max),
+        };
+        (lo..=hi).map(move |y| match op {
+            BaseOp::Div => Fact {
+                op,
+                a: x * y,
+                b: x,
+            },
+            _ => Fact { op, a: x, b: y },
+        })
+    })
+}
+
+/// The different facts the config's trivial rule allows, each counted
+/// once even when "4 + 3" and "3 + 4" are the same fact
+fn acceptable_facts(cfg: &QuizConfig, level: u8, op: BaseOp) -> impl Iterator<Item = Fact> + '_ {
+    possible_facts(level, op).filter(move |f| {
+        !(cfg.exclude_trivial && is_trivial_fact(f))
+            && fact_key(f, cfg.commuted_equal) == (op_index(f.op), f.a, f.b)
+    })
+}
+
+/// Return (question text, answer, kind label)
+fn generate_basic_question(cfg: &QuizConfig, op: BaseOp) -> (String, i32, String) {
+    fact_question_parts(&random_fact(tier_level(&cfg.difficulty), op))
+}
+
+/// Draw a fact that passes the config's trivial/duplicate rules.
+/// `None` means the configured range has run out of acceptable facts.
+fn draw_fact(cfg: &QuizConfig, level: u8, op: BaseOp, used: &[(u8, i32, i32)]) -> Option<Fact> {
+    for _ in 0..MAX_FACT_ATTEMPTS {
+        let fact = random_fact(level, op);
+        if cfg.exclude_trivial && is_trivial_fact(&fact) {
+            continue;
         }
+        if cfg.unique_facts && used.contains(&fact_key(&fact, cfg.commuted_equal)) {
+            continue;
+        }
+        return Some(fact);
+    }
+    // Random draws keep missing: pick from whatever is left
+    let left = || {
+        acceptable_facts(cfg, level, op)
+            .filter(|f| !cfg.unique_facts || !used.contains(&fact_key(f, cfg.commuted_equal)))
+    };
+    let count = left().count();
+    if count == 0 {
+        return None;
     }
+    left().nth(rand_int(0, count as i32 - 1) as usize)
 }
 
 /// Local fallback word problem, in case AI call fails
@@ -173,67 +527,445 @@ fn generate_fallback_word_problem(cfg: &QuizConfig) -> (String, i32, String) {
     )
 }
 
-/// Generate questions, but use placeholder rows for AI word problems.
-/// Guarantee at least 1 AI word problem if cfg.include_words == true.
-fn generate_questions_with_ai_placeholders(cfg: &QuizConfig) -> Vec<Question> {
-    let mut enabled_ops = Vec::new();
+/// Planned counts per type, e.g. "5 +, 3 ×, 2 word"
+fn mix_summary(cfg: &QuizConfig) -> String {
+    question_type_counts(cfg)
+        .iter()
+        .map(|&(t, n)| {
+            let name = match t {
+                QuestionType::Basic(BaseOp::Add) => "+",
+                QuestionType::Basic(BaseOp::Sub) => "−",
+                QuestionType::Basic(BaseOp::Mul) => "×",
+                QuestionType::Basic(BaseOp::Div) => "÷",
+                QuestionType::Word => "word",
+            };
+            format!("{n} {name}")
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Included question types with their mix value, in display order
+fn mix_entries(cfg: &QuizConfig) -> Vec<(QuestionType, u32)> {
+    let mut entries = Vec::new();
     if cfg.include_add {
-        enabled_ops.push(BaseOp::Add);
+        entries.push((QuestionType::Basic(BaseOp::Add), cfg.mix_add));
     }
     if cfg.include_sub {
-        enabled_ops.push(BaseOp::Sub);
+        entries.push((QuestionType::Basic(BaseOp::Sub), cfg.mix_sub));
     }
     if cfg.include_mul {
-        enabled_ops.push(BaseOp::Mul);
+        entries.push((QuestionType::Basic(BaseOp::Mul), cfg.mix_mul));
     }
     if cfg.include_div {
-        enabled_ops.push(BaseOp::Div);
+        entries.push((QuestionType::Basic(BaseOp::Div), cfg.mix_div));
+    }
+    if cfg.include_words {
+        entries.push((QuestionType::Word, cfg.mix_words));
+    }
+    entries
+}
+
+/// Split `total` across `weights` with the largest-remainder method,
+/// so the counts always add up to exactly `total`.
+fn apportion(total: usize, weights: &[u32]) -> Vec<usize> {
+    let sum: u64 = weights.iter().map(|&w| w as u64).sum();
+    if sum == 0 {
+        return vec![0; weights.len()];
+    }
+    let mut counts: Vec<usize> = weights
+        .iter()
+        .map(|&w| (total as u64 * w as u64 / sum) as usize)
+        .collect();
+    let mut by_remainder: Vec<usize> = (0..weights.len()).collect();
+    by_remainder.sort_by_key(|&i| std::cmp::Reverse(total as u64 * weights[i] as u64 % sum));
+    let assigned: usize = counts.iter().sum();
+    for &i in by_remainder.iter().take(total - assigned) {
+        counts[i] += 1;
     }
+    counts
+}
 
-    if enabled_ops.is_empty() && !cfg.include_words {
+/// Exact number of questions per type for this config.
+/// - Percent: `num_questions` split by the mix, every type with a
+///   non-zero share gets at least one question when there is room
+/// - Count: the mix values are used as-is
+fn question_type_counts(cfg: &QuizConfig) -> Vec<(QuestionType, usize)> {
+    let entries = mix_entries(cfg);
+    if entries.is_empty() {
         // force at least addition if nothing chosen
-        enabled_ops.push(BaseOp::Add);
+        let total = match cfg.mix_mode {
+            MixMode::Percent => cfg.num_questions,
+            MixMode::Count => 1,
+        };
+        return vec![(QuestionType::Basic(BaseOp::Add), total)];
+    }
+
+    let counts = match cfg.mix_mode {
+        MixMode::Count => entries.iter().map(|&(_, n)| n as usize).collect(),
+        MixMode::Percent => {
+            let mut weights: Vec<u32> = entries.iter().map(|&(_, w)| w).collect();
+            if weights.iter().all(|&w| w == 0) {
+                weights = vec![1; weights.len()];
+            }
+            let mut counts = apportion(cfg.num_questions, &weights);
+            let wanted = weights.iter().filter(|&&w| w > 0).count();
+            if cfg.num_questions >= wanted {
+                for i in 0..counts.len() {
+                    if weights[i] > 0 && counts[i] == 0 {
+                        let donor = (0..counts.len()).max_by_key(|&j| counts[j]).unwrap();
+                        counts[donor] -= 1;
+                        counts[i] += 1;
+                    }
+                }
+            }
+            counts
+        }
+    };
+
+    let counts: Vec<(QuestionType, usize)> = entries
+        .iter()
+        .zip(counts)
+        .map(|(&(t, _), n)| (t, n))
+        .collect();
+    if counts.iter().all(|&(_, n)| n == 0) {
+        return vec![(QuestionType::Basic(BaseOp::Add), 1)];
     }
+    counts
+}
 
-    let mut questions = Vec::with_capacity(cfg.num_questions);
-    let mut ai_count = 0;
+/// Total quiz length: `num_questions`, or the sum of exact counts
+fn planned_question_total(cfg: &QuizConfig) -> usize {
+    question_type_counts(cfg).iter().map(|&(_, n)| n).sum()
+}
 
-    for _ in 0..cfg.num_questions {
-        let ai_word_enabled = cfg.include_words;
-        let make_word = ai_word_enabled && rand_int(0, 3) == 0; // ~25%
+/// Why exact counts don't make a quiz of allowed length, if they don't
+fn mix_count_error(cfg: &QuizConfig) -> Option<String> {
+    if cfg.mix_mode != MixMode::Count {
+        return None;
+    }
+    let total = planned_question_total(cfg);
+    if (MIN_QUESTIONS..=MAX_QUESTIONS).contains(&total) {
+        return None;
+    }
+    Some(format!(
+        "The exact counts add up to {total} questions. Use {MIN_QUESTIONS} to {MAX_QUESTIONS} in total."
+    ))
+}
 
-        let (prompt, answer, kind) = if make_word {
-            ai_count += 1;
-            (
-                "Loading AI word problem...".to_string(),
-                0,
-                format!("Word Problem 🌟 ({})", difficulty_label(&cfg.difficulty)),
-            )
-        } else {
-            let idx = rand_int(0, (enabled_ops.len() as i32) - 1) as usize;
-            let op = enabled_ops[idx];
-            generate_basic_question(cfg, op)
+/// Switch the mix to `mode`, turning the values over so the quiz stays
+/// the same: percentages become the counts they planned, and counts
+/// become percentages of their total. Excluded types drop to 0.
+fn switch_mix_mode(cfg: &mut QuizConfig, mode: MixMode) {
+    if cfg.mix_mode == mode {
+        return;
+    }
+    let counts = question_type_counts(cfg);
+    let values: Vec<(QuestionType, u32)> = match mode {
+        MixMode::Count => counts.iter().map(|&(t, n)| (t, n as u32)).collect(),
+        MixMode::Percent => {
+            let total: usize = counts.iter().map(|&(_, n)| n).sum();
+            let weights: Vec<u32> = counts.iter().map(|&(_, n)| n as u32).collect();
+            cfg.num_questions = total.clamp(MIN_QUESTIONS, MAX_QUESTIONS);
+            counts
+                .iter()
+                .zip(apportion(100, &weights))
+                .map(|(&(t, _), pct)| (t, pct as u32))
+                .collect()
+        }
+    };
+    let value = |t: QuestionType| {
+        values
+            .iter()
+            .find(|&&(v, _)| v == t)
+            .map_or(0, |&(_, n)| n)
+    };
+    cfg.mix_add = value(QuestionType::Basic(BaseOp::Add));
+    cfg.mix_sub = value(QuestionType::Basic(BaseOp::Sub));
+    cfg.mix_mul = value(QuestionType::Basic(BaseOp::Mul));
+    cfg.mix_div = value(QuestionType::Basic(BaseOp::Div));
+    cfg.mix_words = value(QuestionType::Word);
+    cfg.mix_mode = mode;
+}
+
+/// Question types in quiz order: shuffled, or grouped by type
+fn plan_question_types(cfg: &QuizConfig) -> Vec<QuestionType> {
+    let mut plan = Vec::new();
+    for (t, n) in question_type_counts(cfg) {
+        plan.extend(std::iter::repeat(t).take(n));
+    }
+    if !cfg.group_by_type {
+        shuffle(&mut plan);
+    }
+    plan
+}
+
+/// Why no fresh `op` fact could be drawn
+fn facts_ran_out(cfg: &QuizConfig, level: u8, op: BaseOp) -> String {
+    format!(
+        "Only {} different {} facts fit {} with these settings. \
+         Ask for fewer {} questions, allow repeats or trivial facts, \
+         or pick another difficulty.",
+        acceptable_facts(cfg, level, op).count(),
+        op_symbol(op),
+        difficulty_label(&cfg.difficulty),
+        op_symbol(op),
+    )
+}
+
+/// Generate questions, but use placeholder rows for AI word problems.
+/// The type mix comes from `plan_question_types`, so counts are exact.
+/// Errors when the uniqueness/trivial rules can't be met in range.
+fn generate_questions_with_ai_placeholders(cfg: &QuizConfig) -> Result<Vec<Question>, String> {
+    if let Some(msg) = mix_count_error(cfg) {
+        return Err(msg);
+    }
+    let plan = plan_question_types(cfg);
+    let level = tier_level(&cfg.difficulty);
+    let mut questions = Vec::with_capacity(plan.len());
+    let mut used = Vec::new();
+
+    for slot in plan {
+        let question = match slot {
+            QuestionType::Word => Question {
+                prompt: AI_PLACEHOLDER.to_string(),
+                kind: format!("Word Problem 🌟 ({})", difficulty_label(&cfg.difficulty)),
+                answer: 0,
+                user_answer: String::new(),
+                is_correct: None,
+                unanswered: None,
+                fact: None,
+                level,
+                elapsed_ms: 0.0,
+                focus_started: None,
+                hints_used: 0,
+                attempts: 0,
+                first_try_correct: None,
+            },
+            QuestionType::Basic(op) => {
+                let Some(fact) = draw_fact(cfg, level, op, &used) else {
+                    return Err(facts_ran_out(cfg, level, op));
+                };
+                used.push(fact_key(&fact, cfg.commuted_equal));
+                question_from_fact(fact, level)
+            }
         };
+        questions.push(question);
+    }
 
-        questions.push(Question {
-            prompt,
-            kind,
-            answer,
-            user_answer: String::new(),
-            is_correct: None,
-        });
+    Ok(questions)
+}
+
+/// Fresh, unanswered question for a basic fact
+fn question_from_fact(fact: Fact, level: u8) -> Question {
+    let (prompt, answer, kind) = fact_question_parts(&fact);
+    Question {
+        prompt,
+        kind,
+        answer,
+        user_answer: String::new(),
+        is_correct: None,
+        unanswered: None,
+        fact: Some(fact),
+        level,
+        elapsed_ms: 0.0,
+        focus_started: None,
+        hints_used: 0,
+        attempts: 0,
+        first_try_correct: None,
+    }
+}
+
+/// Quiz that opens with the given facts (due Leitner facts, weakest
+/// mastery facts), then regular questions to fill the planned length.
+/// Each type keeps its count from `question_type_counts`: given facts
+/// only take up their own type's share, and fresh ones fill the rest.
+fn generate_questions_starting_with(cfg: &QuizConfig, due: &[Fact]) -> Result<Vec<Question>, String> {
+    let level = tier_level(&cfg.difficulty);
+    let mut left = question_type_counts(cfg);
+    let mut take = |t: QuestionType| match left.iter_mut().find(|(lt, n)| *lt == t && *n > 0) {
+        Some((_, n)) => {
+            *n -= 1;
+            true
+        }
+        None => false,
+    };
+
+    let mut quiz = Vec::new();
+    let mut used: Vec<(u8, i32, i32)> = Vec::new();
+    for &fact in due {
+        let key = fact_key(&fact, cfg.commuted_equal);
+        if !used.contains(&key) && take(QuestionType::Basic(fact.op)) {
+            used.push(key);
+            quiz.push(question_from_fact(fact, level));
+        }
+    }
+
+    for q in generate_questions_with_ai_placeholders(cfg)? {
+        let kind = match &q.fact {
+            Some(f) if used.contains(&fact_key(f, cfg.commuted_equal)) => continue,
+            Some(f) => QuestionType::Basic(f.op),
+            None => QuestionType::Word,
+        };
+        if take(kind) {
+            if let Some(f) = &q.fact {
+                used.push(fact_key(f, cfg.commuted_equal));
+            }
+            quiz.push(q);
+        }
+    }
+
+    // Fresh facts that clashed with given ones leave gaps to top up
+    for (t, n) in left {
+        let QuestionType::Basic(op) = t else { continue };
+        for _ in 0..n {
+            let fact = draw_fact(cfg, level, op, &used).ok_or_else(|| facts_ran_out(cfg, level, op))?;
+            used.push(fact_key(&fact, cfg.commuted_equal));
+            quiz.push(question_from_fact(fact, level));
+        }
+    }
+    Ok(quiz)
+}
+
+/// Re-roll the basic questions from `from` onward at `level`, keeping
+/// each one's operation. A question keeps its fact if no fresh one fits.
+fn relevel_questions(cfg: &QuizConfig, qs: &mut [Question], from: usize, level: u8) {
+    for i in from..qs.len() {
+        let Some(old) = qs[i].fact else { continue };
+        let used: Vec<(u8, i32, i32)> = qs
+            .iter()
+            .enumerate()
+            .filter(|&(j, _)| j != i)
+            .filter_map(|(_, q)| q.fact.as_ref().map(|f| fact_key(f, cfg.commuted_equal)))
+            .collect();
+        if let Some(fact) = draw_fact(cfg, level, old.op, &used) {
+            let (prompt, answer, kind) = fact_question_parts(&fact);
+            let q = &mut qs[i];
+            q.prompt = prompt;
+            q.answer = answer;
+            q.kind = kind;
+            q.fact = Some(fact);
+            q.level = level;
+        }
+    }
+}
+
+/// What's wrong with an answer box, if it can't be graded
+fn unanswered_reason(user_answer: &str) -> Option<Unanswered> {
+    let trimmed = user_answer.trim();
+    if trimmed.is_empty() {
+        Some(Unanswered::Blank)
+    } else if trimmed.parse::<i32>().is_err() {
+        Some(Unanswered::Invalid)
+    } else {
+        None
+    }
+}
+
+/// Parse and grade one answer: `Some(correct)` for a number, `None`
+/// (with `unanswered` set) for blank or invalid input.
+/// Only a new number (not a re-check of the same one) counts as an attempt.
+fn grade_question(q: &mut Question, clock: &dyn Clock) -> Option<bool> {
+    timing::stop_timing(q, clock);
+    let Ok(val) = q.user_answer.trim().parse::<i32>() else {
+        q.is_correct = None;
+        q.unanswered = unanswered_reason(&q.user_answer);
+        return None;
+    };
+    let ok = val == q.answer;
+    if q.is_correct.is_none() {
+        q.attempts += 1;
+        q.first_try_correct.get_or_insert(ok);
+    }
+    q.is_correct = Some(ok);
+    q.unanswered = None;
+    Some(ok)
+}
+
+/// Needs another go: answered wrong, or left blank/invalid at grading
+fn is_missed(q: &Question) -> bool {
+    q.is_correct == Some(false) || q.unanswered.is_some()
+}
+
+/// Set a missed question up for another try, optionally with new
+/// numbers for the same operation at the same level
+fn prepare_retry(cfg: &QuizConfig, qs: &mut [Question], idx: usize, fresh_numbers: bool) {
+    if fresh_numbers {
+        if let Some(old) = qs[idx].fact {
+            let used: Vec<(u8, i32, i32)> = qs
+                .iter()
+                .filter_map(|q| q.fact.as_ref().map(|f| fact_key(f, cfg.commuted_equal)))
+                .collect();
+            if let Some(fact) = draw_fact(cfg, qs[idx].level, old.op, &used) {
+                let (prompt, answer, kind) = fact_question_parts(&fact);
+                let q = &mut qs[idx];
+                q.prompt = prompt;
+                q.answer = answer;
+                q.kind = kind;
+                q.fact = Some(fact);
+            }
+        }
+    }
+    let q = &mut qs[idx];
+    q.user_answer.clear();
+    q.is_correct = None;
+    q.unanswered = None;
+}
+
+/// Points lost to hints: each hint on a correct answer costs
+/// `pct`% of a point, never more than the whole point
+fn hint_deduction(qs: &[Question], pct: u32) -> f64 {
+    qs.iter()
+        .filter(|q| q.is_correct == Some(true))
+        .map(|q| (q.hints_used as f64 * pct as f64 / 100.0).min(1.0))
+        .sum()
+}
+
+/// "6.25" → "6.25", "6.50" → "6.5", "7.00" → "7"
+fn format_points(p: f64) -> String {
+    let s = format!("{:.2}", p);
+    s.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// Running state of an adaptive quiz
+#[derive(Clone, PartialEq)]
+struct AdaptiveState {
+    start_level: u8,
+    level: u8,
+    peak_level: u8,
+    streak: u32,
+}
+
+fn start_adaptive(diff: &Difficulty) -> AdaptiveState {
+    let level = tier_level(diff);
+    AdaptiveState {
+        start_level: level,
+        level,
+        peak_level: level,
+        streak: 0,
     }
+}
 
-    // Guarantee at least 1 AI word problem if enabled
-    if cfg.include_words && ai_count == 0 {
-        if let Some(first) = questions.get_mut(0) {
-            first.prompt = "Loading AI word problem...".to_string();
-            first.answer = 0;
-            first.kind = format!("Word Problem 🌟 ({})", difficulty_label(&cfg.difficulty));
+/// Move the skill level after one answer: up after a short streak of
+/// correct answers, down straight away on a miss
+fn adapt_level(state: &AdaptiveState, correct: bool) -> AdaptiveState {
+    let mut next = state.clone();
+    if correct {
+        next.streak += 1;
+        if next.streak >= ADAPT_UP_STREAK {
+            next.level = (next.level + 1).min(MAX_LEVEL);
+            next.streak = 0;
         }
+    } else {
+        next.level = next.level.saturating_sub(1).max(MIN_LEVEL);
+        next.streak = 0;
     }
+    next.peak_level = next.peak_level.max(next.level);
+    next
+}
 
-    questions
+fn level_label(level: u8) -> String {
+    format!("level {} ({})", level, difficulty_label(&level_tier(level)))
 }
 
 /// Call your Cloudflare Worker to get a word problem
@@ -273,13 +1005,141 @@ async fn fetch_ai_word_problem(cfg: &QuizConfig) -> Option<(String, i32, String)
     ))
 }
 
+/// Push graded facts into the student's Leitner boxes and mastery
+/// stats, and persist both
+fn record_fact_progress(
+    student: &str,
+    boxes_handle: &UseStateHandle<LeitnerBoxes>,
+    mastery_handle: &UseStateHandle<MasteryStats>,
+    graded: &[Question],
+) {
+    let mut boxes = (**boxes_handle).clone();
+    leitner::record_questions(&mut boxes, graded, leitner::today());
+    leitner::save_boxes(student, &boxes);
+    boxes_handle.set(boxes);
+
+    let mut stats = (**mastery_handle).clone();
+    mastery::record_questions(&mut stats, graded);
+    mastery::save_mastery(student, &stats);
+    mastery_handle.set(stats);
+}
+
+/// Pause on a correct answer before moving to the next question
+const AUTO_ADVANCE_DELAY: Duration = Duration::from_millis(900);
+
+/// Put the Leitner boxes, mastery stats, trophies and history saved
+/// under the student key `key` into their states
+fn load_student_data(
+    key: &str,
+    boxes_handle: &UseStateHandle<LeitnerBoxes>,
+    mastery_handle: &UseStateHandle<MasteryStats>,
+    trophies_handle: &UseStateHandle<achievements::Trophies>,
+    history_handle: &UseStateHandle<history::History>,
+) {
+    boxes_handle.set(leitner::load_boxes(key));
+    mastery_handle.set(mastery::load_mastery(key));
+    trophies_handle.set(achievements::load_trophies(key));
+    history_handle.set(history::load_history(key));
+}
+
+/// Move the one-at-a-time flow past question `idx`. Leaving the last
+/// question finishes the quiz, scored on first tries.
+fn advance_focus(
+    idx: usize,
+    qs: &[Question],
+    current: &UseStateHandle<usize>,
+    score: &UseStateHandle<(usize, usize)>,
+    show_results: &UseStateHandle<bool>,
+) {
+    if idx + 1 >= qs.len() {
+        let first_try = qs.iter().filter(|q| q.first_try_correct == Some(true)).count();
+        score.set((first_try, qs.len()));
+        show_results.set(true);
+    }
+    current.set(idx + 1);
+}
+
+/// Scroll to a question's answer box and put the cursor in it
+fn focus_answer_box(index: usize) {
+    let Some(doc) = web_sys::window().and_then(|w| w.document()) else {
+        return;
+    };
+    if let Some(el) = doc.get_element_by_id(&format!("answer-{index}")) {
+        el.scroll_into_view();
+        if let Ok(input) = el.dyn_into::<HtmlElement>() {
+            let _ = input.focus();
+        }
+    }
+}
+
+/// Top-level screens
+#[derive(Clone, Copy, PartialEq)]
+enum View {
+    Quiz,
+    Mastery,
+    Sprint,
+    Trophies,
+    History,
+}
+
 #[function_component(App)]
 fn app() -> Html {
-    let config = use_state(default_config);
+    let config = use_state(QuizConfig::default);
     let questions = use_state(Vec::<Question>::new);
+    // The questions as last rendered, for async work that finishes after
+    // grading or re-levelling has moved the state on
+    let live_questions = use_mut_ref(Vec::<Question>::new);
     let show_results = use_state(|| false);
     let score = use_state(|| (0usize, 0usize)); // (correct, total)
     let teacher_mode = use_state(|| false);
+    // When the PIN unlock runs out; teacher mode is on only while this is set
+    let unlocked_until = use_state(|| None::<f64>);
+    let gen_error = use_state(|| None::<String>);
+    let current = use_state(|| 0usize); // one-at-a-time position
+    let adaptive_state = use_state(|| None::<AdaptiveState>);
+    let student = use_state(String::new);
+    let leitner_boxes = use_state(|| leitner::load_boxes(""));
+    let mastery_stats = use_state(|| mastery::load_mastery(""));
+    let trophies = use_state(|| achievements::load_trophies(""));
+    let custom_badges = use_state(achievements::load_custom_badges);
+    // Badges unlocked by the quiz on screen
+    let new_badges = use_state(Vec::<achievements::Badge>::new);
+    let history = use_state(|| history::load_history(""));
+    // When the quiz on screen was generated (epoch ms)
+    let started_at = use_state(|| 0.0f64);
+    let profile_list = use_state(profiles::load_profiles);
+    let active_profile = use_state(|| None::<profiles::Profile>);
+    // Classroom kiosk: each student types a name instead of picking a profile
+    let kiosk = use_state(kiosk::load_kiosk);
+    let kiosk_name = use_state(|| None::<String>);
+    let class_log = use_state(kiosk::load_class_log);
+    // Shared device: ask who's playing before anything else
+    let has_profiles = !profile_list.profiles.is_empty() && !*kiosk;
+    let picking = use_state(move || has_profiles);
+    // Setup and seed the quiz on screen came from; `None` if it can't be rebuilt
+    let quiz_origin = use_state(|| None::<(QuizConfig, u64)>);
+    let share_link = use_state(|| None::<String>);
+    // Show the link's QR code big enough to scan off a projector
+    let qr_big = use_state(|| false);
+    // Quiz from the page URL, loaded once a student has been picked
+    let pending_link = use_state(share::doc_from_location);
+    let view = use_state(|| View::Quiz);
+    // Indexes of the questions in the current "Retry missed" round
+    let retry_round = use_state(|| None::<Vec<usize>>);
+    let retry_fresh = use_state(|| false);
+    // Current quiz runs one question at a time (focused or adaptive)
+    let focus_flow = use_state(|| false);
+    // "Check Answers" found blank/invalid answers and is waiting for a second click
+    let review_pending = use_state(|| false);
+    // Timed mode: when the countdown ends, and the clock reading that drives re-renders
+    let deadline = use_state(|| None::<f64>);
+    let now_tick = use_state(|| 0.0f64);
+    let worksheet_settings = use_state(worksheet::load_settings);
+    // A/B/C versions to print instead of the quiz on screen
+    let print_versions = use_state(|| None::<Vec<Vec<Question>>>);
+    let versions_error = use_state(|| None::<String>);
+    // Print once the sheets for this request have rendered
+    let print_pending = use_state(|| false);
 
     // === Config handlers ===
 
@@ -288,7 +1148,7 @@ fn app() -> Html {
         Callback::from(move |e: InputEvent| {
             let input: HtmlInputElement = e.target_unchecked_into();
             let val = input.value().parse::<usize>().unwrap_or(10);
-            let clamped = val.clamp(5, 20);
+            let clamped = val.clamp(MIN_QUESTIONS, MAX_QUESTIONS);
             let mut c = (*config).clone();
             c.num_questions = clamped;
             config.set(c);
@@ -323,6 +1183,13 @@ fn app() -> Html {
                 "mul" => c.include_mul = checked,
                 "div" => c.include_div = checked,
                 "words" => c.include_words = checked,
+                "group" => c.group_by_type = checked,
+                "unique" => c.unique_facts = checked,
+                "commuted" => c.commuted_equal = checked,
+                "trivial" => c.exclude_trivial = checked,
+                "adaptive" => c.adaptive = checked,
+                "focus" => c.one_at_a_time = checked,
+                "review" => c.review_mode = checked,
                 _ => {}
             }
             config.set(c);
@@ -334,40 +1201,278 @@ fn app() -> Html {
     let on_mul = toggle_checkbox("mul", config.clone());
     let on_div = toggle_checkbox("div", config.clone());
     let on_words = toggle_checkbox("words", config.clone());
+    let on_group = toggle_checkbox("group", config.clone());
+    let on_unique = toggle_checkbox("unique", config.clone());
+    let on_commuted = toggle_checkbox("commuted", config.clone());
+    let on_trivial = toggle_checkbox("trivial", config.clone());
+    let on_adaptive = toggle_checkbox("adaptive", config.clone());
+    let on_focus_mode = toggle_checkbox("focus", config.clone());
+    let on_review = toggle_checkbox("review", config.clone());
+
+    let on_time_limit = {
+        let config = config.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlInputElement = e.target_unchecked_into();
+            let mut c = (*config).clone();
+            c.time_limit_secs = select.value().parse::<u32>().unwrap_or(0);
+            config.set(c);
+        })
+    };
 
-    // Teacher mode toggle
-    let on_teacher_mode = {
+    let on_hint_penalty = {
+        let config = config.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut c = (*config).clone();
+            c.hint_penalty_pct = input.value().parse::<u32>().unwrap_or(0).min(100);
+            config.set(c);
+        })
+    };
+
+    let on_student = {
+        let student = student.clone();
+        let leitner_boxes = leitner_boxes.clone();
+        let mastery_stats = mastery_stats.clone();
+        let trophies = trophies.clone();
+        let history = history.clone();
         let teacher_mode = teacher_mode.clone();
         Callback::from(move |e: InputEvent| {
             let input: HtmlInputElement = e.target_unchecked_into();
-            teacher_mode.set(input.checked());
+            if !*teacher_mode {
+                input.set_value(&student);
+                return;
+            }
+            let name = input.value();
+            load_student_data(&name, &leitner_boxes, &mastery_stats, &trophies, &history);
+            student.set(name);
         })
     };
 
-    // === Generate quiz (single async flow) ===
+    // === Student profiles ===
 
-    let on_generate = {
-        let config_handle = config.clone();
-        let questions_state = questions.clone();
+    let on_pick_profile = {
+        let config = config.clone();
+        let questions = questions.clone();
         let show_results = show_results.clone();
-        let score = score.clone();
+        let retry_round = retry_round.clone();
+        let deadline = deadline.clone();
+        let student = student.clone();
+        let leitner_boxes = leitner_boxes.clone();
+        let mastery_stats = mastery_stats.clone();
+        let trophies = trophies.clone();
+        let history = history.clone();
+        let active_profile = active_profile.clone();
+        let picking = picking.clone();
+        let profile_list = profile_list.clone();
+        Callback::from(move |picked: Option<profiles::Profile>| {
+            let key = picked.as_ref().map(|p| p.id.clone()).unwrap_or_default();
+            load_student_data(&key, &leitner_boxes, &mastery_stats, &trophies, &history);
+            if let Some(p) = &picked {
+                // Their own assignment, else their roster group's
+                config.set(profiles::assigned_config(&profile_list, p));
+            }
+            // The last student's quiz isn't theirs to finish
+            questions.set(Vec::new());
+            show_results.set(false);
+            retry_round.set(None);
+            deadline.set(None);
+            student.set(key);
+            active_profile.set(picked);
+            picking.set(false);
+        })
+    };
 
-        Callback::from(move |_| {
-            let cfg = (*config_handle).clone();
-            let questions_state = questions_state.clone();
-            let show_results = show_results.clone();
-            let score = score.clone();
+    let on_switch_student = {
+        let picking = picking.clone();
+        Callback::from(move |_| picking.set(true))
+    };
 
-            spawn_local(async move {
-                log("on_generate: building quiz with placeholders");
-                let mut qs = generate_questions_with_ai_placeholders(&cfg);
-                let total = qs.len();
+    let on_profiles_change = {
+        let profile_list = profile_list.clone();
+        let active_profile = active_profile.clone();
+        let on_pick_profile = on_pick_profile.clone();
+        Callback::from(move |list: profiles::ProfileList| {
+            profiles::save_profiles(&list);
+            if let Some(active) = &*active_profile {
+                match list.profiles.iter().find(|p| p.id == active.id) {
+                    Some(p) => active_profile.set(Some(p.clone())),
+                    None => on_pick_profile.emit(None),
+                }
+            }
+            profile_list.set(list);
+        })
+    };
 
-                // Collect AI indexes from this local vec
-                let ai_indexes: Vec<usize> = qs
-                    .iter()
-                    .enumerate()
-                    .filter(|(_, q)| q.kind.contains("Word Problem"))
+    let on_mix_mode = {
+        let config = config.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlInputElement = e.target_unchecked_into();
+            let mut c = (*config).clone();
+            let mode = match select.value().as_str() {
+                "count" => MixMode::Count,
+                _ => MixMode::Percent,
+            };
+            switch_mix_mode(&mut c, mode);
+            config.set(c);
+        })
+    };
+
+    let mix_input = |field: &'static str,
+                     config: UseStateHandle<QuizConfig>|
+     -> Callback<InputEvent> {
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut c = (*config).clone();
+            // Exact counts share the quiz length limit with the others
+            let max = match c.mix_mode {
+                MixMode::Percent => 100,
+                MixMode::Count => {
+                    let mut others = c.clone();
+                    match field {
+                        "add" => others.include_add = false,
+                        "sub" => others.include_sub = false,
+                        "mul" => others.include_mul = false,
+                        "div" => others.include_div = false,
+                        "words" => others.include_words = false,
+                        _ => {}
+                    }
+                    let taken: u32 = mix_entries(&others).iter().map(|&(_, n)| n).sum();
+                    (MAX_QUESTIONS as u32).saturating_sub(taken)
+                }
+            };
+            let val = input.value().parse::<u32>().unwrap_or(0).min(max);
+            match field {
+                "add" => c.mix_add = val,
+                "sub" => c.mix_sub = val,
+                "mul" => c.mix_mul = val,
+                "div" => c.mix_div = val,
+                "words" => c.mix_words = val,
+                _ => {}
+            }
+            config.set(c);
+        })
+    };
+
+    let on_mix_add = mix_input("add", config.clone());
+    let on_mix_sub = mix_input("sub", config.clone());
+    let on_mix_mul = mix_input("mul", config.clone());
+    let on_mix_div = mix_input("div", config.clone());
+    let on_mix_words = mix_input("words", config.clone());
+
+    // Teacher mode toggle
+    let on_unlock_teacher = {
+        let teacher_mode = teacher_mode.clone();
+        let unlocked_until = unlocked_until.clone();
+        Callback::from(move |_| {
+            unlocked_until.set(Some(BrowserClock.now_ms() + pin::UNLOCK_MS));
+            teacher_mode.set(true);
+        })
+    };
+
+    let on_lock_teacher = {
+        let teacher_mode = teacher_mode.clone();
+        let unlocked_until = unlocked_until.clone();
+        Callback::from(move |_| {
+            unlocked_until.set(None);
+            teacher_mode.set(false);
+        })
+    };
+
+    // Back to student mode when the unlock runs out
+    {
+        let on_lock_teacher = on_lock_teacher.clone();
+        use_effect_with(*unlocked_until, move |&until| {
+            let alive = Rc::new(Cell::new(true));
+            if let Some(until) = until {
+                let alive = alive.clone();
+                spawn_local(async move {
+                    let wait = (until - BrowserClock.now_ms()).max(0.0);
+                    sleep(Duration::from_millis(wait as u64)).await;
+                    if alive.get() {
+                        log("Teacher mode: unlock timed out");
+                        on_lock_teacher.emit(());
+                    }
+                });
+            }
+            move || alive.set(false)
+        });
+    }
+
+    {
+        let live_questions = live_questions.clone();
+        use_effect_with((*questions).clone(), move |qs| {
+            *live_questions.borrow_mut() = qs.clone();
+        });
+    }
+
+    // === Generate quiz (single async flow) ===
+
+    let on_generate = {
+        let config_handle = config.clone();
+        let questions_state = questions.clone();
+        let show_results = show_results.clone();
+        let score = score.clone();
+        let gen_error = gen_error.clone();
+        let current = current.clone();
+        let adaptive_state = adaptive_state.clone();
+        let retry_round = retry_round.clone();
+        let focus_flow = focus_flow.clone();
+        let review_pending = review_pending.clone();
+        let deadline = deadline.clone();
+        let started_at = started_at.clone();
+        let quiz_origin = quiz_origin.clone();
+        let share_link = share_link.clone();
+        let leitner_boxes = leitner_boxes.clone();
+        let live_questions = live_questions.clone();
+
+        Callback::from(move |_: ()| {
+            let cfg = (*config_handle).clone();
+            let due = if cfg.review_mode {
+                leitner::due_facts(&leitner_boxes, leitner::today())
+            } else {
+                Vec::new()
+            };
+            let questions_state = questions_state.clone();
+            let show_results = show_results.clone();
+            let score = score.clone();
+            let gen_error = gen_error.clone();
+            let current = current.clone();
+            let adaptive_state = adaptive_state.clone();
+            let retry_round = retry_round.clone();
+            let focus_flow = focus_flow.clone();
+            let review_pending = review_pending.clone();
+            let deadline = deadline.clone();
+            let started_at = started_at.clone();
+            let quiz_origin = quiz_origin.clone();
+            let share_link = share_link.clone();
+            let live_questions = live_questions.clone();
+
+            spawn_local(async move {
+                log("on_generate: building quiz with placeholders");
+                // Seeded so the quiz can be rebuilt from a share link
+                let seed = random_seed();
+                let generated = if cfg.review_mode {
+                    log(&format!("on_generate: review mode, {} facts due", due.len()));
+                    generate_questions_starting_with(&cfg, &due)
+                } else {
+                    with_seed(seed, || generate_questions_with_ai_placeholders(&cfg))
+                };
+                let qs = match generated {
+                    Ok(qs) => qs,
+                    Err(msg) => {
+                        log(&format!("on_generate: {}", msg));
+                        gen_error.set(Some(msg));
+                        return;
+                    }
+                };
+                gen_error.set(None);
+                let total = qs.len();
+
+                // Collect AI indexes from this local vec
+                let ai_indexes: Vec<usize> = qs
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, q)| q.kind.contains("Word Problem"))
                     .map(|(i, _)| i)
                     .collect();
 
@@ -378,9 +1483,20 @@ fn app() -> Html {
                 ));
 
                 // Show the base quiz with "Loading AI word problem..."
-                questions_state.set(qs.clone());
+                *live_questions.borrow_mut() = qs.clone();
+                questions_state.set(qs);
                 show_results.set(false);
                 score.set((0, total));
+                current.set(0);
+                adaptive_state.set(cfg.adaptive.then(|| start_adaptive(&cfg.difficulty)));
+                retry_round.set(None);
+                focus_flow.set(cfg.one_at_a_time || cfg.adaptive);
+                review_pending.set(false);
+                deadline.set(timing::deadline(cfg.time_limit_secs, &BrowserClock));
+                started_at.set(BrowserClock.now_ms());
+                // Review quizzes depend on this student's boxes, so they can't be shared
+                quiz_origin.set((!cfg.review_mode).then(|| (cfg.clone(), seed)));
+                share_link.set(None);
 
                 // Now fill AI questions sequentially
                 for idx in ai_indexes {
@@ -394,14 +1510,17 @@ fn app() -> Html {
                         generate_fallback_word_problem(&cfg)
                     };
 
-                    if let Some(q) = qs.get_mut(idx) {
-                        q.prompt = prompt;
-                        q.answer = answer;
-                        q.kind = kind;
-                        // Push updated snapshot to state
-                        questions_state.set(qs.clone());
-                    } else {
-                        log(&format!("AI fill: idx {} out of range on local vec", idx));
+                    // Only swap the placeholder; answers given meanwhile stay
+                    let mut qs = live_questions.borrow().clone();
+                    match qs.get_mut(idx) {
+                        Some(q) if q.prompt == AI_PLACEHOLDER => {
+                            q.prompt = prompt;
+                            q.answer = answer;
+                            q.kind = kind;
+                            *live_questions.borrow_mut() = qs.clone();
+                            questions_state.set(qs);
+                        }
+                        _ => log(&format!("AI fill: idx {} is no longer a placeholder", idx)),
                     }
                 }
             });
@@ -413,10 +1532,12 @@ fn app() -> Html {
     let on_regen_ai = {
         let config_handle = config.clone();
         let questions_state = questions.clone();
+        let live_questions = live_questions.clone();
 
         Callback::from(move |idx: usize| {
             let cfg = (*config_handle).clone();
             let questions_state = questions_state.clone();
+            let live_questions = live_questions.clone();
 
             spawn_local(async move {
                 log(&format!("Regen: idx {} -> calling Worker", idx));
@@ -429,11 +1550,12 @@ fn app() -> Html {
                     generate_fallback_word_problem(&cfg)
                 };
 
-                let mut qs = (*questions_state).clone();
+                let mut qs = live_questions.borrow().clone();
                 if let Some(q) = qs.get_mut(idx) {
                     q.prompt = prompt;
                     q.answer = answer;
                     q.kind = kind;
+                    *live_questions.borrow_mut() = qs.clone();
                     questions_state.set(qs);
                 } else {
                     log(&format!("Regen: idx {} out of range on update", idx));
@@ -448,222 +1570,1653 @@ fn app() -> Html {
         let questions_state = questions.clone();
         let show_results = show_results.clone();
         let score = score.clone();
+        let current = current.clone();
+        let adaptive_state = adaptive_state.clone();
+        let retry_round = retry_round.clone();
+        let review_pending = review_pending.clone();
+        let deadline = deadline.clone();
+        let time_limit_secs = config.time_limit_secs;
         Callback::from(move |_| {
             let mut qs = (*questions_state).clone();
             for q in &mut qs {
                 q.user_answer.clear();
                 q.is_correct = None;
+                q.unanswered = None;
+                q.hints_used = 0;
+                q.elapsed_ms = 0.0;
+                q.attempts = 0;
+                q.first_try_correct = None;
             }
+            retry_round.set(None);
+            review_pending.set(false);
+            deadline.set(timing::deadline(time_limit_secs, &BrowserClock));
             let total = qs.len();
             questions_state.set(qs);
             show_results.set(false);
             score.set((0, total));
+            current.set(0);
+            if let Some(state) = (*adaptive_state).clone() {
+                adaptive_state.set(Some(AdaptiveState {
+                    level: state.start_level,
+                    peak_level: state.start_level,
+                    streak: 0,
+                    ..state
+                }));
+            }
         })
     };
 
-    // === Grade quiz ===
+    // === Submit the current question (one-at-a-time flow) ===
 
-    let on_check_answers = {
+    let on_submit_current = {
+        let config_handle = config.clone();
         let questions_state = questions.clone();
         let show_results = show_results.clone();
         let score = score.clone();
+        let current = current.clone();
+        let adaptive_state = adaptive_state.clone();
+        let student = student.clone();
+        let leitner_boxes = leitner_boxes.clone();
+        let mastery_stats = mastery_stats.clone();
         Callback::from(move |_| {
+            let cfg = (*config_handle).clone();
             let mut qs = (*questions_state).clone();
-            let mut correct = 0usize;
-            let total = qs.len();
-            for q in &mut qs {
-                let trimmed = q.user_answer.trim();
-                if let Ok(val) = trimmed.parse::<i32>() {
-                    let ok = val == q.answer;
-                    if ok {
-                        correct += 1;
+            let idx = *current;
+            let Some(q) = qs.get_mut(idx) else { return };
+            if q.is_correct == Some(true) {
+                // already moving on
+                return;
+            }
+            let attempts_before = q.attempts;
+            let Some(ok) = grade_question(q, &BrowserClock) else {
+                // Blank or not a number: warn, don't count it
+                questions_state.set(qs);
+                return;
+            };
+
+            // Stats and level only follow the first try at each question
+            if attempts_before == 0 {
+                record_fact_progress(&student, &leitner_boxes, &mastery_stats, &qs[idx..=idx]);
+                if let Some(state) = (*adaptive_state).clone() {
+                    let next = adapt_level(&state, ok);
+                    if next.level != state.level {
+                        log(&format!("Adaptive: level {} -> {}", state.level, next.level));
+                        relevel_questions(&cfg, &mut qs, idx + 1, next.level);
                     }
-                    q.is_correct = Some(ok);
-                } else {
-                    q.is_correct = Some(false);
+                    adaptive_state.set(Some(next));
                 }
             }
+
+            questions_state.set(qs.clone());
+            if ok {
+                let current = current.clone();
+                let score = score.clone();
+                let show_results = show_results.clone();
+                spawn_local(async move {
+                    sleep(AUTO_ADVANCE_DELAY).await;
+                    advance_focus(idx, &qs, &current, &score, &show_results);
+                });
+            }
+        })
+    };
+
+    let on_skip_current = {
+        let questions_state = questions.clone();
+        let show_results = show_results.clone();
+        let score = score.clone();
+        let current = current.clone();
+        let student = student.clone();
+        let leitner_boxes = leitner_boxes.clone();
+        let mastery_stats = mastery_stats.clone();
+        Callback::from(move |_| {
+            let mut qs = (*questions_state).clone();
+            let idx = *current;
+            let Some(q) = qs.get_mut(idx) else { return };
+            if q.is_correct.is_none() {
+                let attempts_before = q.attempts;
+                grade_question(q, &BrowserClock);
+                if attempts_before == 0 && q.attempts == 1 {
+                    record_fact_progress(&student, &leitner_boxes, &mastery_stats, &qs[idx..=idx]);
+                }
+            }
+            questions_state.set(qs.clone());
+            advance_focus(idx, &qs, &current, &score, &show_results);
+        })
+    };
+
+    // === Grade quiz ===
+
+    let grade_now = {
+        let questions_state = questions.clone();
+        let show_results = show_results.clone();
+        let score = score.clone();
+        let student = student.clone();
+        let leitner_boxes = leitner_boxes.clone();
+        let mastery_stats = mastery_stats.clone();
+        let retry_round = retry_round.clone();
+        let review_pending = review_pending.clone();
+        let deadline = deadline.clone();
+        Callback::from(move |_: ()| {
+            let mut qs = (*questions_state).clone();
+            let round: Vec<usize> = match &*retry_round {
+                Some(round) => round.clone(),
+                None => (0..qs.len()).collect(),
+            };
+            let mut correct = 0usize;
+            let total = round.len();
+            for &i in &round {
+                if grade_question(&mut qs[i], &BrowserClock) == Some(true) {
+                    correct += 1;
+                }
+            }
+            // Only the first grading of a round counts towards the boxes
+            if !*show_results {
+                let graded: Vec<Question> = round.iter().map(|&i| qs[i].clone()).collect();
+                record_fact_progress(&student, &leitner_boxes, &mastery_stats, &graded);
+            }
             score.set((correct, total));
             questions_state.set(qs);
             show_results.set(true);
+            review_pending.set(false);
+            deadline.set(None);
+        })
+    };
+
+    // First click lists blank/invalid answers; checking again grades anyway
+    let on_check_answers = {
+        let questions_state = questions.clone();
+        let show_results = show_results.clone();
+        let retry_round = retry_round.clone();
+        let review_pending = review_pending.clone();
+        let grade_now = grade_now.clone();
+        Callback::from(move |_| {
+            let has_unanswered = questions_state.iter().enumerate().any(|(i, q)| {
+                let in_round = match &*retry_round {
+                    Some(round) => round.contains(&i),
+                    None => true,
+                };
+                in_round && unanswered_reason(&q.user_answer).is_some()
+            });
+            if has_unanswered && !*review_pending && !*show_results {
+                review_pending.set(true);
+            } else {
+                grade_now.emit(());
+            }
+        })
+    };
+
+    let on_grade_anyway = {
+        let grade_now = grade_now.clone();
+        Callback::from(move |_| grade_now.emit(()))
+    };
+
+    // === Countdown (timed mode) ===
+
+    // Re-render about every quarter second while a timed quiz is running
+    {
+        let now_tick = now_tick.clone();
+        use_effect_with((*deadline, *show_results), move |&(deadline, finished)| {
+            let alive = Rc::new(Cell::new(true));
+            if deadline.is_some() && !finished {
+                let alive = alive.clone();
+                spawn_local(async move {
+                    while alive.get() {
+                        now_tick.set(BrowserClock.now_ms());
+                        sleep(Duration::from_millis(250)).await;
+                    }
+                });
+            }
+            move || alive.set(false)
+        });
+    }
+
+    let secs_left = deadline.map(|d| timing::seconds_left(d, &BrowserClock));
+    let time_up = !*show_results && secs_left == Some(0);
+
+    // Time's up: submit through the normal grading path, blanks and all
+    {
+        let grade_now = grade_now.clone();
+        use_effect_with(time_up, move |&up| {
+            if up {
+                log("Timer: time's up, grading");
+                grade_now.emit(());
+            }
+            || ()
+        });
+    }
+
+    // Name shown in headings and saved with each session
+    let who = match &*active_profile {
+        Some(p) => p.name.clone(),
+        None if student.trim().is_empty() => "guest".to_string(),
+        None => student.trim().to_string(),
+    };
+
+    // === History and badges ===
+
+    // A quiz counts once, when its first round of results appears
+    {
+        let history = history.clone();
+        let config = (*config).clone();
+        let score = *score;
+        let started_ms = *started_at;
+        let trophies = trophies.clone();
+        let new_badges = new_badges.clone();
+        let questions = (*questions).clone();
+        let difficulty = config.difficulty.clone();
+        let mastery = (*mastery_stats).clone();
+        let badges: Vec<achievements::Badge> = achievements::builtin_badges()
+            .into_iter()
+            .chain((*custom_badges).clone())
+            .collect();
+        let student = (*student).clone();
+        let who = who.clone();
+        let first_round = retry_round.is_none();
+        let in_kiosk = *kiosk;
+        let class_log = class_log.clone();
+        use_effect_with(*show_results, move |&shown| {
+            if !shown {
+                new_badges.set(Vec::new());
+            } else if first_round {
+                let session = history::session_from_quiz(
+                    &who,
+                    &config,
+                    &questions,
+                    score,
+                    started_ms,
+                    BrowserClock.now_ms(),
+                );
+                if in_kiosk {
+                    let mut log = (*class_log).clone();
+                    history::add_session(&mut log, session.clone());
+                    kiosk::save_class_log(&log);
+                    class_log.set(log);
+                }
+                let mut h = (*history).clone();
+                history::add_session(&mut h, session);
+                history::save_history(&student, &h);
+                history.set(h);
+
+                let mut t = (*trophies).clone();
+                let earned = achievements::record_quiz(
+                    &mut t,
+                    &badges,
+                    &questions,
+                    &difficulty,
+                    &mastery,
+                    leitner::today(),
+                );
+                achievements::save_trophies(&student, &t);
+                trophies.set(t);
+                new_badges.set(earned);
+            }
+            || ()
+        });
+    }
+
+    // === Save / open quiz files ===
+
+    // Put a ready-made quiz on screen, exactly as given
+    let on_load_quiz = {
+        let config = config.clone();
+        let questions = questions.clone();
+        let show_results = show_results.clone();
+        let score = score.clone();
+        let gen_error = gen_error.clone();
+        let current = current.clone();
+        let adaptive_state = adaptive_state.clone();
+        let retry_round = retry_round.clone();
+        let focus_flow = focus_flow.clone();
+        let review_pending = review_pending.clone();
+        let deadline = deadline.clone();
+        let started_at = started_at.clone();
+        let quiz_origin = quiz_origin.clone();
+        let share_link = share_link.clone();
+        let view = view.clone();
+        Callback::from(move |(cfg, qs): (QuizConfig, Vec<Question>)| {
+            score.set((0, qs.len()));
+            questions.set(qs);
+            show_results.set(false);
+            current.set(0);
+            // A fixed quiz isn't re-levelled, even if it was made in adaptive mode
+            adaptive_state.set(None);
+            retry_round.set(None);
+            focus_flow.set(cfg.one_at_a_time || cfg.adaptive);
+            review_pending.set(false);
+            deadline.set(timing::deadline(cfg.time_limit_secs, &BrowserClock));
+            started_at.set(BrowserClock.now_ms());
+            quiz_origin.set(None);
+            share_link.set(None);
+            gen_error.set(None);
+            config.set(cfg);
+            view.set(View::Quiz);
+        })
+    };
+
+    // Saved files carry every answer, and opening one replaces the setup
+    let on_save_quiz = {
+        let config = config.clone();
+        let questions = questions.clone();
+        let teacher_mode = teacher_mode.clone();
+        Callback::from(move |_| {
+            if !*teacher_mode {
+                return;
+            }
+            let now = BrowserClock.now_ms();
+            let json = quiz_file::to_json(&config, &questions, now);
+            export::download(&export::file_name("quiz", now, ".json"), "application/json", &json);
+        })
+    };
+
+    let on_open_quiz = {
+        let gen_error = gen_error.clone();
+        let on_load_quiz = on_load_quiz.clone();
+        let teacher_mode = teacher_mode.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let Some(file) = input.files().and_then(|files| files.get(0)) else {
+                return;
+            };
+            if !*teacher_mode {
+                input.set_value("");
+                return;
+            }
+            // Let the same file be picked again later
+            input.set_value("");
+            let gen_error = gen_error.clone();
+            let on_load_quiz = on_load_quiz.clone();
+            spawn_local(async move {
+                let text = match JsFuture::from(file.text()).await {
+                    Ok(text) => text.as_string().unwrap_or_default(),
+                    Err(err) => {
+                        log(&format!("Open quiz: could not read file: {:?}", err));
+                        gen_error.set(Some("Could not read that file.".to_string()));
+                        return;
+                    }
+                };
+                match quiz_file::from_json(&text) {
+                    Ok(loaded) => {
+                        log(&format!("Open quiz: {} questions", loaded.1.len()));
+                        on_load_quiz.emit(loaded);
+                    }
+                    Err(msg) => gen_error.set(Some(msg)),
+                }
+            });
+        })
+    };
+
+    // === Share links ===
+
+    let on_share = {
+        let quiz_origin = quiz_origin.clone();
+        let questions = questions.clone();
+        let share_link = share_link.clone();
+        Callback::from(move |_| {
+            let Some((cfg, seed)) = (*quiz_origin).clone() else {
+                return;
+            };
+            let doc = share::ShareDoc {
+                v: share::VERSION,
+                config: cfg,
+                seed,
+                words: share::fixed_words(&questions),
+            };
+            share_link.set(Some(share::share_url(&doc)));
+        })
+    };
+
+    let on_qr_big = {
+        let qr_big = qr_big.clone();
+        Callback::from(move |_| qr_big.set(!*qr_big))
+    };
+
+    let on_copy_link = {
+        let share_link = share_link.clone();
+        Callback::from(move |_| {
+            if let (Some(link), Some(window)) = ((*share_link).clone(), web_sys::window()) {
+                let _ = window.navigator().clipboard().write_text(&link);
+            }
+        })
+    };
+
+    // Open the linked quiz in student mode once we know who's playing
+    {
+        let pending_link = pending_link.clone();
+        let on_load_quiz = on_load_quiz.clone();
+        let quiz_origin = quiz_origin.clone();
+        let teacher_mode = teacher_mode.clone();
+        let gen_error = gen_error.clone();
+        let waiting = *picking;
+        use_effect_with((waiting, pending_link.is_some()), move |&(waiting, pending)| {
+            if !waiting && pending {
+                match (*pending_link).clone() {
+                    Some(Ok(doc)) => match share::rebuild(&doc) {
+                        Ok(qs) => {
+                            log(&format!("Share link: rebuilt {} questions", qs.len()));
+                            on_load_quiz.emit((doc.config.clone(), qs));
+                            quiz_origin.set(Some((doc.config, doc.seed)));
+                            teacher_mode.set(false);
+                        }
+                        Err(msg) => gen_error.set(Some(format!(
+                            "This quiz link can't be rebuilt: {msg}"
+                        ))),
+                    },
+                    Some(Err(msg)) => gen_error.set(Some(msg)),
+                    None => {}
+                }
+                pending_link.set(None);
+            }
+            || ()
+        });
+    }
+
+    // === CSV export ===
+
+    let on_export_results = {
+        let questions = questions.clone();
+        let config = config.clone();
+        let score = score.clone();
+        let who = who.clone();
+        Callback::from(move |_| {
+            let now = BrowserClock.now_ms();
+            let csv = export::quiz_csv(&who, now, &config.difficulty, *score, &questions);
+            export::download(&export::file_name(&who, now, ".csv"), "text/csv", &csv);
+        })
+    };
+
+    // === Classroom kiosk ===
+
+    // Clear the screen for the next student's name
+    let on_kiosk_next = {
+        let questions = questions.clone();
+        let show_results = show_results.clone();
+        let score = score.clone();
+        let gen_error = gen_error.clone();
+        let current = current.clone();
+        let adaptive_state = adaptive_state.clone();
+        let retry_round = retry_round.clone();
+        let focus_flow = focus_flow.clone();
+        let review_pending = review_pending.clone();
+        let deadline = deadline.clone();
+        let share_link = share_link.clone();
+        let student = student.clone();
+        let leitner_boxes = leitner_boxes.clone();
+        let mastery_stats = mastery_stats.clone();
+        let trophies = trophies.clone();
+        let history = history.clone();
+        let active_profile = active_profile.clone();
+        let kiosk_name = kiosk_name.clone();
+        Callback::from(move |_| {
+            questions.set(Vec::new());
+            show_results.set(false);
+            score.set((0, 0));
+            gen_error.set(None);
+            current.set(0);
+            adaptive_state.set(None);
+            retry_round.set(None);
+            focus_flow.set(false);
+            review_pending.set(false);
+            deadline.set(None);
+            share_link.set(None);
+            load_student_data("", &leitner_boxes, &mastery_stats, &trophies, &history);
+            student.set(String::new());
+            active_profile.set(None);
+            kiosk_name.set(None);
+        })
+    };
+
+    let on_kiosk_start = {
+        let student = student.clone();
+        let leitner_boxes = leitner_boxes.clone();
+        let mastery_stats = mastery_stats.clone();
+        let trophies = trophies.clone();
+        let history = history.clone();
+        let kiosk_name = kiosk_name.clone();
+        Callback::from(move |name: String| {
+            load_student_data(&name, &leitner_boxes, &mastery_stats, &trophies, &history);
+            student.set(name.clone());
+            kiosk_name.set(Some(name));
+        })
+    };
+
+    // A name was entered: build their quiz once their data has loaded
+    {
+        let on_generate = on_generate.clone();
+        use_effect_with(kiosk_name.is_some(), move |&named| {
+            if named {
+                on_generate.emit(());
+            }
+            || ()
+        });
+    }
+
+    // Results stay up for a while, then the next student's turn
+    {
+        let on_kiosk_next = on_kiosk_next.clone();
+        use_effect_with((*kiosk, *show_results), move |&(on, shown)| {
+            let alive = Rc::new(Cell::new(true));
+            if on && shown {
+                let alive = alive.clone();
+                spawn_local(async move {
+                    sleep(kiosk::RESET_AFTER).await;
+                    if alive.get() {
+                        on_kiosk_next.emit(());
+                    }
+                });
+            }
+            move || alive.set(false)
+        });
+    }
+
+    let on_enter_kiosk = {
+        let kiosk = kiosk.clone();
+        let view = view.clone();
+        let on_lock_teacher = on_lock_teacher.clone();
+        let on_kiosk_next = on_kiosk_next.clone();
+        Callback::from(move |_| {
+            kiosk::save_kiosk(true);
+            kiosk.set(true);
+            view.set(View::Quiz);
+            on_kiosk_next.emit(());
+            on_lock_teacher.emit(());
+        })
+    };
+
+    // The PIN was right: back to the normal app, in teacher mode
+    let on_exit_kiosk = {
+        let kiosk = kiosk.clone();
+        let on_kiosk_next = on_kiosk_next.clone();
+        let on_unlock_teacher = on_unlock_teacher.clone();
+        Callback::from(move |_| {
+            kiosk::save_kiosk(false);
+            kiosk.set(false);
+            on_kiosk_next.emit(());
+            on_unlock_teacher.emit(());
+        })
+    };
+
+    let on_export_class_log = {
+        let class_log = class_log.clone();
+        Callback::from(move |_| {
+            let now = BrowserClock.now_ms();
+            export::download(
+                &export::file_name("class-log", now, ".csv"),
+                "text/csv",
+                &export::history_csv(&class_log),
+            );
+        })
+    };
+
+    let on_clear_class_log = {
+        let class_log = class_log.clone();
+        Callback::from(move |_| {
+            let log = history::History::default();
+            kiosk::save_class_log(&log);
+            class_log.set(log);
+        })
+    };
+
+    let on_add_badge = {
+        let custom_badges = custom_badges.clone();
+        Callback::from(move |badge: achievements::Badge| {
+            let mut list = (*custom_badges).clone();
+            list.push(badge);
+            achievements::save_custom_badges(&list);
+            custom_badges.set(list);
+        })
+    };
+
+    let on_remove_badge = {
+        let custom_badges = custom_badges.clone();
+        Callback::from(move |id: String| {
+            let mut list = (*custom_badges).clone();
+            list.retain(|b| b.id != id);
+            achievements::save_custom_badges(&list);
+            custom_badges.set(list);
+        })
+    };
+
+    // === Retry only the missed questions ===
+
+    let on_retry_missed = {
+        let config_handle = config.clone();
+        let questions_state = questions.clone();
+        let show_results = show_results.clone();
+        let score = score.clone();
+        let adaptive_state = adaptive_state.clone();
+        let retry_round = retry_round.clone();
+        let retry_fresh = retry_fresh.clone();
+        let focus_flow = focus_flow.clone();
+        let review_pending = review_pending.clone();
+        let deadline = deadline.clone();
+        Callback::from(move |_| {
+            let cfg = (*config_handle).clone();
+            let mut qs = (*questions_state).clone();
+            let missed: Vec<usize> = (0..qs.len())
+                .filter(|&i| is_missed(&qs[i]))
+                .collect();
+            if missed.is_empty() {
+                return;
+            }
+            for &i in &missed {
+                prepare_retry(&cfg, &mut qs, i, *retry_fresh);
+            }
+            log(&format!("Retry missed: {} questions", missed.len()));
+            score.set((0, missed.len()));
+            retry_round.set(Some(missed));
+            adaptive_state.set(None);
+            focus_flow.set(false);
+            review_pending.set(false);
+            deadline.set(None);
+            questions_state.set(qs);
+            show_results.set(false);
+        })
+    };
+
+    let on_retry_fresh = {
+        let retry_fresh = retry_fresh.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            retry_fresh.set(input.checked());
+        })
+    };
+
+    // === Practice weakest facts (from the mastery heatmap) ===
+
+    let on_focus_fact = {
+        let config_handle = config.clone();
+        let questions_state = questions.clone();
+        let show_results = show_results.clone();
+        let score = score.clone();
+        let gen_error = gen_error.clone();
+        let current = current.clone();
+        let adaptive_state = adaptive_state.clone();
+        let retry_round = retry_round.clone();
+        let focus_flow = focus_flow.clone();
+        let review_pending = review_pending.clone();
+        let deadline = deadline.clone();
+        let started_at = started_at.clone();
+        let quiz_origin = quiz_origin.clone();
+        let share_link = share_link.clone();
+        let mastery_stats = mastery_stats.clone();
+        let view = view.clone();
+        Callback::from(move |fact: Fact| {
+            // Same length and level as the current setup, but only this operation
+            let mut cfg = (*config_handle).clone();
+            cfg.num_questions = planned_question_total(&cfg);
+            cfg.mix_mode = MixMode::Percent;
+            cfg.include_add = fact.op == BaseOp::Add;
+            cfg.include_sub = fact.op == BaseOp::Sub;
+            cfg.include_mul = fact.op == BaseOp::Mul;
+            cfg.include_div = fact.op == BaseOp::Div;
+            cfg.include_words = false;
+
+            let picked = fact_key(&fact, false);
+            let mut focus = vec![fact];
+            focus.extend(
+                mastery::weakest_facts(&mastery_stats, fact.op, cfg.num_questions)
+                    .into_iter()
+                    .filter(|f| fact_key(f, false) != picked),
+            );
+
+            match generate_questions_starting_with(&cfg, &focus) {
+                Ok(qs) => {
+                    log(&format!("Focus quiz: {} questions around {}", qs.len(), fact_text(&fact)));
+                    score.set((0, qs.len()));
+                    questions_state.set(qs);
+                    show_results.set(false);
+                    current.set(0);
+                    adaptive_state.set(None);
+                    retry_round.set(None);
+                    focus_flow.set(cfg.one_at_a_time);
+                    review_pending.set(false);
+                    deadline.set(timing::deadline(cfg.time_limit_secs, &BrowserClock));
+                    started_at.set(BrowserClock.now_ms());
+                    quiz_origin.set(None);
+                    share_link.set(None);
+                    gen_error.set(None);
+                }
+                Err(msg) => gen_error.set(Some(msg)),
+            }
+            view.set(View::Quiz);
         })
     };
 
     // === Print quiz ===
 
+    // Prints the worksheet below, not the screen; answers only go on the key page
     let on_print = {
+        let print_versions = print_versions.clone();
+        let print_pending = print_pending.clone();
         let teacher_mode = teacher_mode.clone();
         Callback::from(move |_| {
-            // Optional: nudge them to turn on teacher mode before printing
-            log(&format!("Print clicked (teacher_mode = {})", *teacher_mode));
-            if let Some(win) = web_sys::window() {
-                let _ = win.print();
+            if !*teacher_mode {
+                return;
+            }
+            print_versions.set(None);
+            print_pending.set(true);
+        })
+    };
+
+    let on_print_versions = {
+        let config = config.clone();
+        let worksheet_settings = worksheet_settings.clone();
+        let print_versions = print_versions.clone();
+        let versions_error = versions_error.clone();
+        let print_pending = print_pending.clone();
+        Callback::from(move |_| {
+            let count = worksheet_settings.versions.clamp(2, versions::MAX_VERSIONS) as usize;
+            match versions::generate_versions(&config, count, random_seed()) {
+                Ok(sheets) => {
+                    versions_error.set(None);
+                    print_versions.set(Some(sheets));
+                    print_pending.set(true);
+                }
+                Err(err) => versions_error.set(Some(err)),
+            }
+        })
+    };
+
+    {
+        let print_pending = print_pending.clone();
+        use_effect_with(*print_pending, move |pending| {
+            if *pending {
+                if let Some(win) = web_sys::window() {
+                    let _ = win.print();
+                }
+                print_pending.set(false);
             }
+            || ()
+        });
+    }
+
+    let on_worksheet_settings = {
+        let worksheet_settings = worksheet_settings.clone();
+        Callback::from(move |settings: worksheet::WorksheetSettings| {
+            worksheet::save_settings(&settings);
+            worksheet_settings.set(settings);
         })
     };
 
+    let show_view = |target: View| {
+        let view = view.clone();
+        Callback::from(move |_| view.set(target))
+    };
+
     let (correct_count, total_count) = *score;
+    let hints_off = hint_deduction(&questions, config.hint_penalty_pct);
+    let in_round = |idx: usize| match &*retry_round {
+        Some(round) => round.contains(&idx),
+        None => true,
+    };
+    let missed_count = questions.iter().filter(|q| is_missed(q)).count();
+    let in_round_questions = || questions.iter().enumerate().filter(|(i, _)| in_round(*i));
+    let unanswered_items: Vec<(usize, Unanswered)> = in_round_questions()
+        .filter_map(|(i, q)| unanswered_reason(&q.user_answer).map(|r| (i, r)))
+        .collect();
+    let wrong_count = in_round_questions().filter(|(_, q)| q.is_correct == Some(false)).count();
+    let blank_count = in_round_questions()
+        .filter(|(_, q)| q.unanswered == Some(Unanswered::Blank))
+        .count();
+    let invalid_count = in_round_questions()
+        .filter(|(_, q)| q.unanswered == Some(Unanswered::Invalid))
+        .count();
+    let first_try_count = questions
+        .iter()
+        .filter(|q| q.first_try_correct == Some(true))
+        .count();
+    let eventual_count = questions.iter().filter(|q| q.is_correct == Some(true)).count();
+    let retried: Vec<String> = questions
+        .iter()
+        .enumerate()
+        .filter(|(_, q)| q.attempts > 1)
+        .map(|(i, q)| format!("Q{} ×{}", i + 1, q.attempts))
+        .collect();
+    let pct_of = |n: usize, d: usize| {
+        if d == 0 {
+            0
+        } else {
+            (n as f64 / d as f64 * 100.0).round() as i32
+        }
+    };
+    let error_summary = diagnosis::summarize(&questions)
+        .iter()
+        .map(|&(p, n)| format!("{} ({})", diagnosis::pattern_label(p), n))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let slow_facts: Vec<String> = questions
+        .iter()
+        .filter(|q| timing::slow_but_correct(q))
+        .map(|q| q.prompt.clone())
+        .collect();
+    let one_at_a_time = *focus_flow && !*show_results;
+    let results_screen = *focus_flow && *show_results;
+
+    // The printed sheet carries answers only for an unlocked teacher; a
+    // student's Ctrl+P gets questions alone
+    let sheet_settings = worksheet::WorksheetSettings {
+        answer_key: worksheet_settings.answer_key && *teacher_mode,
+        ..(*worksheet_settings).clone()
+    };
+    // Word problems still on their way from the Worker
+    let filling_ai = questions.iter().any(|q| q.prompt == AI_PLACEHOLDER);
+    let mix_unit = match config.mix_mode {
+        MixMode::Percent => "%",
+        MixMode::Count => "questions",
+    };
+    let mix_summary = mix_summary(&config);
+    let mix_row = |label: &'static str, enabled: bool, value: u32, oninput: Callback<InputEvent>| {
+        html! {
+            <div class="checkbox-row mix-row">
+                <input
+                    class="mix-input"
+                    type="number"
+                    min="0"
+                    disabled={!enabled}
+                    value={value.to_string()}
+                    {oninput}
+                />
+                <span>{format!("{label} ({mix_unit})")}</span>
+            </div>
+        }
+    };
+
+    if *picking {
+        return html! {
+            <div class="app-shell">
+                <div class="card">
+                    <h1>{"Math Quest 🎒"}</h1>
+                    <h2>{"Who's practicing today?"}</h2>
+                    <profiles::ProfilePicker
+                        profiles={profile_list.profiles.clone()}
+                        on_pick={on_pick_profile}
+                    />
+                </div>
+            </div>
+        };
+    }
 
     html! {
         <div class="app-shell">
-            <div class="card">
-                <h1>{"Math Quest 🎒"}</h1>
-                <div class="subtitle">
-                    {"Build a custom 2nd–3rd grade math quiz with 5–20 questions, "}
-                    {"including AI-generated word problems that match the difficulty."}
+            <div class="worksheet">
+                if let Some(sheets) = (*print_versions).clone() {
+                    { for sheets.into_iter().enumerate().map(|(i, qs)| html! {
+                        <worksheet::Worksheet
+                            questions={qs}
+                            settings={sheet_settings.clone()}
+                            label={Some(AttrValue::from(versions::version_label(i)))}
+                        />
+                    }) }
+                } else {
+                    <worksheet::Worksheet
+                        questions={(*questions).clone()}
+                        settings={sheet_settings.clone()}
+                        link={(*share_link).clone().map(AttrValue::from)}
+                    />
+                }
+            </div>
+            if !*kiosk {
+                <div class="tab-row">
+                    <button
+                        class={if *view == View::Quiz { "btn-primary" } else { "btn-secondary" }}
+                        onclick={show_view(View::Quiz)}
+                    >
+                        {"Quiz"}
+                    </button>
+                    <button
+                        class={if *view == View::Mastery { "btn-primary" } else { "btn-secondary" }}
+                        onclick={show_view(View::Mastery)}
+                    >
+                        {"Mastery"}
+                    </button>
+                    <button
+                        class={if *view == View::Sprint { "btn-primary" } else { "btn-secondary" }}
+                        onclick={show_view(View::Sprint)}
+                    >
+                        {"Mad Minute"}
+                    </button>
+                    <button
+                        class={if *view == View::Trophies { "btn-primary" } else { "btn-secondary" }}
+                        onclick={show_view(View::Trophies)}
+                    >
+                        {"Trophies"}
+                    </button>
+                    <button
+                        class={if *view == View::History { "btn-primary" } else { "btn-secondary" }}
+                        onclick={show_view(View::History)}
+                    >
+                        {"History"}
+                    </button>
                 </div>
+            }
 
-                <div class="config-grid">
-                    <div>
-                        <div class="field-label">
-                            <span>{"Number of questions"}</span>
-                            <span class="field-hint">{"5 to 20"}</span>
-                        </div>
-                        <input
-                            class="field-input"
-                            type="number"
-                            min="5"
-                            max="20"
-                            value={config.num_questions.to_string()}
-                            oninput={on_num_questions}
+            if *view == View::Quiz {
+                if *kiosk {
+                    <div class="card kiosk-card">
+                        <kiosk::KioskBar
+                            name={(*kiosk_name).clone().map(AttrValue::from)}
+                            finished={*show_results}
+                            on_start={on_kiosk_start}
+                            on_check={on_check_answers.clone()}
+                            on_next={on_kiosk_next}
+                            on_exit={on_exit_kiosk}
                         />
                     </div>
-
-                    <div>
-                        <div class="field-label">
-                            <span>{"Difficulty"}</span>
+                } else {
+                    <div class="card">
+                        <h1>{"Math Quest 🎒"}</h1>
+                        <div class="subtitle">
+                            {"Build a custom 2nd–3rd grade math quiz with 5–20 questions, "}
+                            {"including AI-generated word problems that match the difficulty."}
                         </div>
-                        <select
-                            class="field-input"
-                            onchange={on_difficulty_change}
-                            value={
-                                match config.difficulty {
-                                    Difficulty::Easy => "easy",
-                                    Difficulty::Moderate => "moderate",
-                                    Difficulty::Advanced => "advanced",
-                                }.to_string()
-                            }
-                        >
-                            <option value="easy">{"Easy – single-digit + small ×/÷"}</option>
-                            <option value="moderate">{"Moderate – two-digit + bigger ×/÷"}</option>
-                            <option value="advanced">{"Advanced – three-digit + challenge ×/÷"}</option>
-                        </select>
-                    </div>
 
-                    <div>
-                        <div class="field-label">
-                            <span>{"Question types"}</span>
-                        </div>
-                        <div class="checkbox-row">
-                            <input type="checkbox" checked={config.include_add} oninput={on_add} />
-                            <span>{"+ (Add)"}</span>
-                        </div>
-                        <div class="checkbox-row">
-                            <input type="checkbox" checked={config.include_sub} oninput={on_sub} />
-                            <span>{"− (Subtract)"}</span>
-                        </div>
-                        <div class="checkbox-row">
-                            <input type="checkbox" checked={config.include_mul} oninput={on_mul} />
-                            <span>{"× (Multiply)"}</span>
-                        </div>
-                        <div class="checkbox-row">
-                            <input type="checkbox" checked={config.include_div} oninput={on_div} />
-                            <span>{"÷ (Divide)"}</span>
-                        </div>
-                    </div>
+                        <div class="config-grid">
+                            <div>
+                                <div class="field-label">
+                                    <span>{"Student"}</span>
+                                    <span class="field-hint">{"keeps review boxes separate"}</span>
+                                </div>
+                                if let Some(p) = &*active_profile {
+                                    <div class="checkbox-row">
+                                        <span class="profile-avatar">{p.avatar.clone()}</span>
+                                        <span>{format!("{} ({} grade)", p.name, profiles::grade_label(p.grade))}</span>
+                                    </div>
+                                } else {
+                                    <input
+                                        class="field-input"
+                                        type="text"
+                                        placeholder="First name"
+                                        readonly={!*teacher_mode}
+                                        value={(*student).clone()}
+                                        oninput={on_student}
+                                    />
+                                }
+                                if !profile_list.profiles.is_empty() {
+                                    <button class="btn-secondary" onclick={on_switch_student}>
+                                        {"Switch student"}
+                                    </button>
+                                }
+                            </div>
 
-                    <div>
-                        <div class="field-label">
-                            <span>{"Extras"}</span>
-                        </div>
-                        <div class="checkbox-row">
-                            <input type="checkbox" checked={config.include_words} oninput={on_words} />
-                            <span>{"Include AI word problems"}</span>
+                            <fieldset class="config-lock" disabled={!*teacher_mode}>
+                                <div>
+                                    <div class="field-label">
+                                        <span>{"Number of questions"}</span>
+                                        <span class="field-hint">{"5 to 20"}</span>
+                                    </div>
+                                    <input
+                                        class="field-input"
+                                        type="number"
+                                        min="5"
+                                        max="20"
+                                        disabled={config.mix_mode == MixMode::Count}
+                                        value={planned_question_total(&config).to_string()}
+                                        oninput={on_num_questions}
+                                    />
+                                    if let Some(msg) = mix_count_error(&config) {
+                                        <div class="gen-error">{msg}</div>
+                                    }
+                                </div>
+
+                                <div>
+                                    <div class="field-label">
+                                        <span>{"Difficulty"}</span>
+                                    </div>
+                                    <select
+                                        class="field-input"
+                                        onchange={on_difficulty_change}
+                                        value={
+                                            match config.difficulty {
+                                                Difficulty::Easy => "easy",
+                                                Difficulty::Moderate => "moderate",
+                                                Difficulty::Advanced => "advanced",
+                                            }.to_string()
+                                        }
+                                    >
+                                        <option value="easy">{"Easy – single-digit + small ×/÷"}</option>
+                                        <option value="moderate">{"Moderate – two-digit + bigger ×/÷"}</option>
+                                        <option value="advanced">{"Advanced – three-digit + challenge ×/÷"}</option>
+                                    </select>
+                                </div>
+
+                                <div>
+                                    <div class="field-label">
+                                        <span>{"Time limit"}</span>
+                                        <span class="field-hint">{"auto-checks when time runs out"}</span>
+                                    </div>
+                                    <select
+                                        class="field-input"
+                                        onchange={on_time_limit}
+                                        value={config.time_limit_secs.to_string()}
+                                    >
+                                        <option value="0">{"Off – no timer"}</option>
+                                        <option value="60">{"1 minute"}</option>
+                                        <option value="120">{"2 minutes"}</option>
+                                        <option value="180">{"3 minutes"}</option>
+                                        <option value="300">{"5 minutes"}</option>
+                                        <option value="600">{"10 minutes"}</option>
+                                    </select>
+                                </div>
+
+                                <div>
+                                    <div class="field-label">
+                                        <span>{"Question types"}</span>
+                                    </div>
+                                    <div class="checkbox-row">
+                                        <input type="checkbox" checked={config.include_add} oninput={on_add} />
+                                        <span>{"+ (Add)"}</span>
+                                    </div>
+                                    <div class="checkbox-row">
+                                        <input type="checkbox" checked={config.include_sub} oninput={on_sub} />
+                                        <span>{"− (Subtract)"}</span>
+                                    </div>
+                                    <div class="checkbox-row">
+                                        <input type="checkbox" checked={config.include_mul} oninput={on_mul} />
+                                        <span>{"× (Multiply)"}</span>
+                                    </div>
+                                    <div class="checkbox-row">
+                                        <input type="checkbox" checked={config.include_div} oninput={on_div} />
+                                        <span>{"÷ (Divide)"}</span>
+                                    </div>
+                                    <div class="checkbox-row">
+                                        <input type="checkbox" checked={config.unique_facts} oninput={on_unique} />
+                                        <span>{"No repeated facts"}</span>
+                                    </div>
+                                    <div class="checkbox-row">
+                                        <input
+                                            type="checkbox"
+                                            checked={config.commuted_equal}
+                                            disabled={!config.unique_facts}
+                                            oninput={on_commuted}
+                                        />
+                                        <span>{"3 + 4 and 4 + 3 count as the same"}</span>
+                                    </div>
+                                    <div class="checkbox-row">
+                                        <input type="checkbox" checked={config.exclude_trivial} oninput={on_trivial} />
+                                        <span>{"Skip trivial facts (0, 1, doubles)"}</span>
+                                    </div>
+                                </div>
+
+                                <div>
+                                    <div class="field-label">
+                                        <span>{"Question mix"}</span>
+                                    </div>
+                                    <select class="field-input" onchange={on_mix_mode}>
+                                        <option value="percent" selected={config.mix_mode == MixMode::Percent}>
+                                            {"Percent of the quiz"}
+                                        </option>
+                                        <option value="count" selected={config.mix_mode == MixMode::Count}>
+                                            {"Exact counts"}
+                                        </option>
+                                    </select>
+                                    { mix_row("+", config.include_add, config.mix_add, on_mix_add) }
+                                    { mix_row("−", config.include_sub, config.mix_sub, on_mix_sub) }
+                                    { mix_row("×", config.include_mul, config.mix_mul, on_mix_mul) }
+                                    { mix_row("÷", config.include_div, config.mix_div, on_mix_div) }
+                                    { mix_row("Word", config.include_words, config.mix_words, on_mix_words) }
+                                    <div class="checkbox-row">
+                                        <input type="checkbox" checked={config.group_by_type} oninput={on_group} />
+                                        <span>{"Group questions by type"}</span>
+                                    </div>
+                                    <div class="tiny-note">{format!("This quiz: {mix_summary}")}</div>
+                                </div>
+
+                                <div>
+                                    <div class="field-label">
+                                        <span>{"Extras"}</span>
+                                    </div>
+                                    <div class="checkbox-row">
+                                        <input type="checkbox" checked={config.include_words} oninput={on_words} />
+                                        <span>{"Include AI word problems"}</span>
+                                    </div>
+                                    <div class="checkbox-row">
+                                        <input type="checkbox" checked={config.review_mode} oninput={on_review} />
+                                        <span>{format!(
+                                            "Review mode ({} missed facts due)",
+                                            leitner::due_facts(&leitner_boxes, leitner::today()).len()
+                                        )}</span>
+                                    </div>
+                                    <div class="checkbox-row">
+                                        <input type="checkbox" checked={config.one_at_a_time} oninput={on_focus_mode} />
+                                        <span>{"One question at a time (instant feedback)"}</span>
+                                    </div>
+                                    <div class="checkbox-row">
+                                        <input type="checkbox" checked={config.adaptive} oninput={on_adaptive} />
+                                        <span>{"Adaptive (one question at a time, level follows answers)"}</span>
+                                    </div>
+                                    <div class="checkbox-row mix-row">
+                                        <input
+                                            class="mix-input"
+                                            type="number"
+                                            min="0"
+                                            max="100"
+                                            step="5"
+                                            value={config.hint_penalty_pct.to_string()}
+                                            oninput={on_hint_penalty}
+                                        />
+                                        <span>{"% of a point off per hint"}</span>
+                                    </div>
+                                    <div class="tiny-note">
+                                        {"Word problems come from your Cloudflare/OpenAI Worker; "}
+                                        {"if it fails, a local backup problem is used."}
+                                    </div>
+                                </div>
+                            </fieldset>
                         </div>
-                        <div class="checkbox-row">
-                            <input type="checkbox" checked={*teacher_mode} oninput={on_teacher_mode} />
-                            <span>{"Teacher mode (show answers & print)"}</span>
+
+                        <pin::TeacherLock
+                            unlocked_until={*unlocked_until}
+                            on_unlock={on_unlock_teacher}
+                            on_lock={on_lock_teacher}
+                        />
+
+                        <div class="btn-row">
+                            <button class="btn-primary" onclick={on_generate.reform(|_| ())}>
+                                {"Generate Quiz"}
+                            </button>
+                            <button class="btn-secondary" onclick={on_check_answers}>
+                                {"Check Answers"}
+                            </button>
+                            <button class="btn-secondary" onclick={on_reset_answers}>
+                                {"Clear Answers"}
+                            </button>
+                            <button class="btn-secondary" disabled={!*teacher_mode} onclick={on_print}>
+                                {"Print Quiz"}
+                            </button>
+                            <button
+                                class="btn-secondary"
+                                disabled={!*teacher_mode || questions.is_empty() || filling_ai}
+                                title="Saves once every word problem has loaded"
+                                onclick={on_save_quiz}
+                            >
+                                {"Save Quiz"}
+                            </button>
+                            <label class="btn-secondary file-button">
+                                {"Open Quiz"}
+                                <input
+                                    type="file"
+                                    accept=".json,application/json"
+                                    disabled={!*teacher_mode}
+                                    onchange={on_open_quiz}
+                                />
+                            </label>
+                            <button
+                                class="btn-secondary"
+                                disabled={quiz_origin.is_none()}
+                                title="Generate a quiz first (review quizzes can't be shared)"
+                                onclick={on_share}
+                            >
+                                {"Share Link"}
+                            </button>
                         </div>
+                        if let Some(link) = (*share_link).clone() {
+                            <div class="share-panel">
+                                <input class="field-input" type="text" readonly=true value={link.clone()} />
+                                <button class="btn-secondary" onclick={on_copy_link}>{"Copy"}</button>
+                            </div>
+                            <div class="tiny-note">
+                                {"Anyone opening this link gets this same quiz, in student mode."}
+                            </div>
+                            <div class="btn-row">
+                                <button class="btn-secondary" onclick={on_qr_big}>
+                                    {if *qr_big { "Smaller QR code" } else { "Project QR code" }}
+                                </button>
+                            </div>
+                            <qr::QrView
+                                text={link}
+                                class={classes!(qr_big.then_some("qr-big"))}
+                            />
+                        }
                         <div class="tiny-note">
-                            {"Word problems come from your Cloudflare/OpenAI Worker; "}
-                            {"if it fails, a local backup problem is used."}
+                            {"All answers are whole numbers—perfect for 2nd and 3rd graders."}
                         </div>
                     </div>
-                </div>
-
-                <div class="btn-row">
-                    <button class="btn-primary" onclick={on_generate}>
-                        {"Generate Quiz"}
-                    </button>
-                    <button class="btn-secondary" onclick={on_check_answers}>
-                        {"Check Answers"}
-                    </button>
-                    <button class="btn-secondary" onclick={on_reset_answers}>
-                        {"Clear Answers"}
-                    </button>
-                    <button class="btn-secondary" onclick={on_print}>
-                        {"Print Quiz"}
-                    </button>
-                </div>
-                <div class="tiny-note">
-                    {"All answers are whole numbers—perfect for 2nd and 3rd graders."}
-                </div>
-            </div>
+                }
 
-            <div class="card">
-                <h2>{"Your Quiz"}</h2>
+                if !*kiosk || kiosk_name.is_some() {
+                    <div class="card">
+                        <h2>{"Your Quiz"}</h2>
+                        if let Some(link) = (*share_link).clone() {
+                            <div class="quiz-qr">
+                                <qr::QrView text={link} />
+                                <div class="tiny-note">{"Scan to open this quiz on a tablet."}</div>
+                            </div>
+                        }
 
-                if questions.is_empty() {
-                    <p>{"Click “Generate Quiz” to create a new set of questions."}</p>
-                } else {
-                    <div class="questions-wrap">
-                        { for questions.iter().enumerate().map(|(idx, q)| {
-                            let idx_copy = idx;
-                            let questions_state = questions.clone();
-                            html! {
-                                <QuestionRow
-                                    index={idx_copy}
-                                    question={q.clone()}
-                                    questions_state={questions_state}
-                                    show_results={*show_results}
-                                    teacher_mode={*teacher_mode}
-                                    on_regen_ai={on_regen_ai.clone()}
-                                />
+                        if let Some(secs) = secs_left {
+                            if !*show_results {
+                                <div class={classes!("countdown", (secs <= 30).then_some("low"))}>
+                                    {format!("⏱ {} left", timing::format_clock(secs))}
+                                </div>
                             }
-                        }) }
-                    </div>
-
-                    if *show_results {
-                        <div class="score-banner">
-                            <div>
-                                <span class="score-main">
-                                    {format!("Score: {}/{}", correct_count, total_count)}
-                                </span>
-                                {"  "}
-                                {
-                                    if total_count > 0 {
-                                        let pct = (correct_count as f64 / total_count as f64 * 100.0).round() as i32;
-                                        format!("({}% correct)", pct)
-                                    } else {
-                                        "".into()
-                                    }
+                        }
+
+                        if let Some(msg) = (*gen_error).clone() {
+                            <div class="gen-error">{msg}</div>
+                        }
+
+                        if *review_pending && !*show_results {
+                            <div class="review-panel">
+                                if unanswered_items.is_empty() {
+                                    <div>{"Every question has an answer now. 👍"}</div>
+                                } else {
+                                    <div>{"Before checking, these still need an answer:"}</div>
+                                    <div class="btn-row">
+                                        { for unanswered_items.iter().map(|&(i, reason)| {
+                                            let label = match reason {
+                                                Unanswered::Blank => format!("Question {} – blank", i + 1),
+                                                Unanswered::Invalid => format!("Question {} – not a number", i + 1),
+                                            };
+                                            html! {
+                                                <button
+                                                    class="btn-secondary"
+                                                    onclick={Callback::from(move |_| focus_answer_box(i))}
+                                                >
+                                                    {label}
+                                                </button>
+                                            }
+                                        }) }
+                                    </div>
                                 }
+                                <div class="btn-row">
+                                    <button class="btn-primary" onclick={on_grade_anyway}>
+                                        {"Check answers now"}
+                                    </button>
+                                </div>
                             </div>
-                            <div class="tiny-note">
-                                {
-                                    if correct_count == total_count && total_count > 0 {
-                                        "Perfect score! 🏆"
-                                    } else if correct_count * 2 >= total_count {
-                                        "Nice work! Look over the ones marked in red and try again. 💪"
-                                    } else {
-                                        "Great practice round. Try a new quiz or pick an easier level and build up! 🌱"
+                        }
+
+                        if questions.is_empty() {
+                            <p>{"Click “Generate Quiz” to create a new set of questions."}</p>
+                        } else {
+                            if one_at_a_time {
+                                if let Some(q) = questions.get(*current) {
+                                    <div class="progress-bar">
+                                        <div
+                                            class="progress-fill"
+                                            style={format!("width: {}%;", pct_of(*current, questions.len()))}
+                                        ></div>
+                                    </div>
+                                    <div class="progress-note">
+                                        {format!("Question {} of {}", *current + 1, questions.len())}
+                                        if *teacher_mode {
+                                            if let Some(state) = (*adaptive_state).clone() {
+                                                {format!(" · now at {}", level_label(state.level))}
+                                            }
+                                        }
+                                    </div>
+                                    <div class="questions-wrap">
+                                        <QuestionRow
+                                            index={*current}
+                                            question={q.clone()}
+                                            questions_state={questions.clone()}
+                                            show_results={false}
+                                            teacher_mode={*teacher_mode}
+                                            on_regen_ai={on_regen_ai.clone()}
+                                        />
+                                    </div>
+                                    {
+                                        match q.is_correct {
+                                            Some(true) => html! {
+                                                <div class="feedback correct">{"✅ Correct! On to the next one…"}</div>
+                                            },
+                                            Some(false) => html! {
+                                                <div class="feedback incorrect">
+                                                    {format!("❌ Not quite — try again! (try {} so far)", q.attempts)}
+                                                </div>
+                                            },
+                                            None => match q.unanswered {
+                                                Some(Unanswered::Blank) => html! {
+                                                    <div class="feedback unanswered">{"✏️ Type an answer first."}</div>
+                                                },
+                                                Some(Unanswered::Invalid) => html! {
+                                                    <div class="feedback unanswered">
+                                                        {format!("✏️ “{}” isn't a whole number. Try again.", q.user_answer.trim())}
+                                                    </div>
+                                                },
+                                                None => Html::default(),
+                                            },
+                                        }
                                     }
+                                    <div class="btn-row">
+                                        <button
+                                            class="btn-primary"
+                                            disabled={q.is_correct == Some(true)}
+                                            onclick={on_submit_current}
+                                        >
+                                            {"Submit ➜"}
+                                        </button>
+                                        <button
+                                            class="btn-secondary"
+                                            disabled={q.is_correct == Some(true)}
+                                            onclick={on_skip_current}
+                                        >
+                                            {"Skip"}
+                                        </button>
+                                    </div>
                                 }
-                            </div>
+                            } else {
+                                if results_screen {
+                                    <table class="results-table">
+                                        <tr>
+                                            <th>{"#"}</th>
+                                            <th>{"Question"}</th>
+                                            <th>{"Answer"}</th>
+                                            <th>{"Tries"}</th>
+                                            <th>{"Time"}</th>
+                                            <th></th>
+                                        </tr>
+                                        { for questions.iter().enumerate().map(|(i, q)| html! {
+                                            <tr>
+                                                <td>{i + 1}</td>
+                                                <td>{q.prompt.clone()}</td>
+                                                <td>{q.answer}</td>
+                                                <td>{q.attempts}</td>
+                                                <td>{timing::format_secs(q.elapsed_ms)}</td>
+                                                <td>
+                                                    {
+                                                        match (q.first_try_correct, q.is_correct) {
+                                                            (Some(true), _) => "✅ first try",
+                                                            (_, Some(true)) => "✔️ got there",
+                                                            _ => "❌ skipped",
+                                                        }
+                                                    }
+                                                    if timing::slow_but_correct(q) {
+                                                        {" 🐢"}
+                                                    }
+                                                </td>
+                                            </tr>
+                                        }) }
+                                    </table>
+                                } else {
+                                <div class="questions-wrap">
+                                    { for questions.iter().enumerate().filter(|(idx, _)| in_round(*idx)).map(|(idx, q)| {
+                                        let idx_copy = idx;
+                                        let questions_state = questions.clone();
+                                        html! {
+                                            <QuestionRow
+                                                index={idx_copy}
+                                                question={q.clone()}
+                                                questions_state={questions_state}
+                                                show_results={*show_results}
+                                                teacher_mode={*teacher_mode}
+                                                on_regen_ai={on_regen_ai.clone()}
+                                            />
+                                        }
+                                    }) }
+                                </div>
+                                }
+
+                                if *show_results {
+                                    <div class="score-banner">
+                                        <div>
+                                            <span class="score-main">
+                                                {format!("Score: {}/{}", correct_count, total_count)}
+                                            </span>
+                                            {"  "}
+                                            {
+                                                if total_count > 0 {
+                                                    let pct = (correct_count as f64 / total_count as f64 * 100.0).round() as i32;
+                                                    format!("({}% correct)", pct)
+                                                } else {
+                                                    "".into()
+                                                }
+                                            }
+                                        </div>
+                                        if hints_off > 0.0 {
+                                            <div class="tiny-note">
+                                                {format!(
+                                                    "Hints used: −{} → {}/{} points",
+                                                    format_points(hints_off),
+                                                    format_points(correct_count as f64 - hints_off),
+                                                    total_count
+                                                )}
+                                            </div>
+                                        }
+                                        if wrong_count + blank_count + invalid_count > 0 {
+                                            <div class="tiny-note">
+                                                {format!(
+                                                    "{} wrong · {} left blank · {} not a number",
+                                                    wrong_count, blank_count, invalid_count
+                                                )}
+                                            </div>
+                                        }
+                                        if !retried.is_empty() {
+                                            <div class="tiny-note">
+                                                {format!(
+                                                    "First try: {}/{} ({}%) · Eventually: {}/{} ({}%)",
+                                                    first_try_count,
+                                                    questions.len(),
+                                                    pct_of(first_try_count, questions.len()),
+                                                    eventual_count,
+                                                    questions.len(),
+                                                    pct_of(eventual_count, questions.len()),
+                                                )}
+                                            </div>
+                                            <div class="tiny-note">
+                                                {format!("Tries: {}", retried.join(", "))}
+                                            </div>
+                                        }
+                                        if !error_summary.is_empty() {
+                                            <div class="tiny-note">
+                                                {format!("Mistake patterns: {}", error_summary)}
+                                            </div>
+                                        }
+                                        if !new_badges.is_empty() {
+                                            <div class="new-badges">
+                                                {"New badge! "}
+                                                { for new_badges.iter().map(|b| html! {
+                                                    <span class="trophy-chip">{format!("{} {}", b.icon, b.name)}</span>
+                                                }) }
+                                            </div>
+                                        }
+                                        if !slow_facts.is_empty() {
+                                            <div class="tiny-note">
+                                                {format!(
+                                                    "🐢 Correct but slow (not fluent yet): {}",
+                                                    slow_facts.join(", ")
+                                                )}
+                                            </div>
+                                        }
+                                        if *teacher_mode {
+                                            if let Some(state) = (*adaptive_state).clone() {
+                                                <div class="tiny-note">
+                                                    {format!(
+                                                        "Adaptive: started at {}, finished at {}, peak {}.",
+                                                        level_label(state.start_level),
+                                                        level_label(state.level),
+                                                        level_label(state.peak_level),
+                                                    )}
+                                                </div>
+                                            }
+                                        }
+                                        <div class="tiny-note">
+                                            {
+                                                if correct_count == total_count && total_count > 0 {
+                                                    "Perfect score! 🏆"
+                                                } else if correct_count * 2 >= total_count {
+                                                    "Nice work! Look over the ones marked in red and try again. 💪"
+                                                } else {
+                                                    "Great practice round. Try a new quiz or pick an easier level and build up! 🌱"
+                                                }
+                                            }
+                                        </div>
+                                    </div>
+                                    <div class="btn-row">
+                                        <button class="btn-secondary" onclick={on_export_results}>
+                                            {"Export results (CSV)"}
+                                        </button>
+                                    </div>
+                                    if missed_count > 0 {
+                                        <div class="btn-row">
+                                            <button class="btn-primary" onclick={on_retry_missed}>
+                                                {format!("Retry missed ({})", missed_count)}
+                                            </button>
+                                            <div class="checkbox-row">
+                                                <input type="checkbox" checked={*retry_fresh} oninput={on_retry_fresh} />
+                                                <span>{"with new numbers"}</span>
+                                            </div>
+                                        </div>
+                                    }
+                                }
+                            }
+                        }
+                    </div>
+                }
+            }
+
+            if *view == View::Mastery {
+                <div class="card">
+                    <h2>{format!(
+                        "Fact mastery – {}",
+                        who
+                    )}</h2>
+                    <MasteryView stats={(*mastery_stats).clone()} on_pick={on_focus_fact} />
+                </div>
+            }
+
+            if *view == View::Sprint {
+                <div class="card">
+                    <h2>{"Mad Minute sprint"}</h2>
+                    <sprint::SprintView config={(*config).clone()} />
+                </div>
+            }
+
+            if *view == View::History {
+                <div class="card">
+                    <h2>{format!(
+                        "Progress – {}",
+                        who
+                    )}</h2>
+                    <history::HistoryView history={(*history).clone()} student={who.clone()} />
+                </div>
+            }
+
+            if *view == View::Trophies {
+                <div class="card">
+                    <h2>{format!(
+                        "Trophy shelf – {}",
+                        who
+                    )}</h2>
+                    <achievements::TrophyShelf
+                        badges={achievements::builtin_badges().into_iter().chain((*custom_badges).clone()).collect::<Vec<_>>()}
+                        trophies={(*trophies).clone()}
+                        today={leitner::today()}
+                        teacher_mode={*teacher_mode}
+                        on_add={on_add_badge}
+                        on_remove={on_remove_badge}
+                    />
+                </div>
+            }
+
+            if *teacher_mode {
+                <div class="card">
+                    <h2>{format!(
+                        "Review boxes – {}",
+                        who
+                    )}</h2>
+                    if leitner_boxes.cards.is_empty() {
+                        <p class="tiny-note">{"No facts tracked yet. Grade a quiz to fill the boxes."}</p>
+                    } else {
+                        <div class="leitner-grid">
+                            { for (1..=leitner::BOX_COUNT).map(|box_num| {
+                                let today = leitner::today();
+                                let cards = leitner::cards_in_box(&leitner_boxes, box_num);
+                                let due = cards.iter().filter(|c| c.due_day <= today).count();
+                                html! {
+                                    <div class="leitner-box">
+                                        <div class="field-label">
+                                            <span>{format!("Box {box_num}")}</span>
+                                            <span class="field-hint">{format!("{} facts, {} due", cards.len(), due)}</span>
+                                        </div>
+                                        <div class="leitner-facts">
+                                            { for cards.iter().map(|c| html! {
+                                                <span class={classes!(
+                                                    "leitner-fact",
+                                                    (c.due_day <= today).then_some("due")
+                                                )}>
+                                                    {fact_text(&c.fact)}
+                                                </span>
+                                            }) }
+                                        </div>
+                                    </div>
+                                }
+                            }) }
                         </div>
                     }
-                }
-            </div>
+                </div>
+            }
+
+            if *teacher_mode {
+                <div class="card">
+                    <h2>{"Printable worksheet"}</h2>
+                    <worksheet::WorksheetSettingsForm
+                        settings={(*worksheet_settings).clone()}
+                        on_change={on_worksheet_settings}
+                    />
+                    <div class="btn-row">
+                        <button class="btn-secondary" onclick={on_print_versions}>
+                            {format!("Print {} versions", worksheet_settings.versions)}
+                        </button>
+                    </div>
+                    if let Some(msg) = (*versions_error).clone() {
+                        <div class="gen-error">{msg}</div>
+                    }
+                    <div class="tiny-note">
+                        {"“Print Quiz” prints the current questions with these settings. "}
+                        {"“Print versions” makes new versions from the setup above, each with "}
+                        {"the same mix and difficulty but different numbers, and its own answer key. "}
+                        {"Word problems use the offline template so every version is ready to print."}
+                    </div>
+                </div>
+            }
+
+            if *teacher_mode {
+                <div class="card">
+                    <h2>{"Classroom kiosk"}</h2>
+                    <div class="tiny-note">
+                        {"For center rotations: hides the setup and teacher controls, asks each "}
+                        {"student for a first name, and clears the screen after their results. "}
+                        {"Kiosk quizzes use the setup above. Leaving kiosk mode needs the teacher PIN."}
+                    </div>
+                    <div class="btn-row">
+                        <button class="btn-primary" onclick={on_enter_kiosk}>
+                            {"Start kiosk mode"}
+                        </button>
+                        <button
+                            class="btn-secondary"
+                            disabled={class_log.sessions.is_empty()}
+                            onclick={on_export_class_log}
+                        >
+                            {format!("Export class log ({} results)", class_log.sessions.len())}
+                        </button>
+                        <button
+                            class="btn-secondary"
+                            disabled={class_log.sessions.is_empty()}
+                            onclick={on_clear_class_log}
+                        >
+                            {"Clear class log"}
+                        </button>
+                    </div>
+                </div>
+            }
+
+            if *teacher_mode {
+                <div class="card">
+                    <h2>{"Students on this device"}</h2>
+                    <profiles::ProfileManager
+                        list={(*profile_list).clone()}
+                        current_config={(*config).clone()}
+                        on_change={on_profiles_change.clone()}
+                    />
+                    <h2>{"Class roster"}</h2>
+                    <roster::RosterManager
+                        list={(*profile_list).clone()}
+                        current_config={(*config).clone()}
+                        on_change={on_profiles_change}
+                    />
+                </div>
+            }
 
             <div class="tiny-note">
                 {"Security note: your OpenAI key stays in Cloudflare; this app calls only your Worker URL."}
@@ -702,6 +3255,49 @@ fn question_row(props: &QuestionRowProps) -> Html {
             if let Some(q) = qs.get_mut(index) {
                 q.user_answer = value;
                 q.is_correct = None;
+                q.unanswered = None;
+            }
+            questions_state.set(qs);
+        })
+    };
+
+    // Time on task: count the time the answer box has focus
+    let on_focus = {
+        let questions_state = questions_state.clone();
+        Callback::from(move |_: FocusEvent| {
+            let mut qs = (*questions_state).clone();
+            if let Some(q) = qs.get_mut(index) {
+                if q.focus_started.is_none() {
+                    timing::start_timing(q, &BrowserClock);
+                    questions_state.set(qs);
+                }
+            }
+        })
+    };
+
+    let on_blur = {
+        let questions_state = questions_state.clone();
+        Callback::from(move |_: FocusEvent| {
+            let mut qs = (*questions_state).clone();
+            if let Some(q) = qs.get_mut(index) {
+                timing::stop_timing(q, &BrowserClock);
+            }
+            questions_state.set(qs);
+        })
+    };
+
+    let hints = hints::hints_for(&question);
+    let hints_shown = (question.hints_used as usize).min(hints.len());
+
+    let on_hint = {
+        let questions_state = questions_state.clone();
+        let available = hints.len();
+        Callback::from(move |_| {
+            let mut qs = (*questions_state).clone();
+            if let Some(q) = qs.get_mut(index) {
+                if (q.hints_used as usize) < available {
+                    q.hints_used += 1;
+                }
             }
             questions_state.set(qs);
         })
@@ -723,20 +3319,56 @@ fn question_row(props: &QuestionRowProps) -> Html {
                 html! {
                     <div class="feedback incorrect">
                         {format!("❌ Not quite. Correct answer: {}", question.answer)}
+                        { solutions::view_solution(&solutions::worked_solution(&question)) }
                     </div>
                 }
             }
         } else {
-            Html::default()
+            match question.unanswered {
+                Some(Unanswered::Blank) => html! {
+                    <div class="feedback unanswered">
+                        {format!("⚠️ Not answered. Correct answer: {}", question.answer)}
+                    </div>
+                },
+                Some(Unanswered::Invalid) => html! {
+                    <div class="feedback unanswered">
+                        {format!(
+                            "⚠️ “{}” isn't a whole number. Correct answer: {}",
+                            question.user_answer.trim(),
+                            question.answer
+                        )}
+                    </div>
+                },
+                None => Html::default(),
+            }
         }
     } else {
         Html::default()
     };
 
     let teacher_answer = if teacher_mode {
+        let diagnosis = if show_results {
+            diagnosis::diagnose_question(&question)
+        } else {
+            None
+        };
         html! {
             <div class="teacher-answer">
                 {format!("Answer (teacher): {}", question.answer)}
+                if question.hints_used > 0 {
+                    {format!(" · {} hint(s) used", question.hints_used)}
+                }
+                if let Some(p) = diagnosis {
+                    <span class="diagnosis">
+                        {format!(" · likely error: {}", diagnosis::pattern_label(p))}
+                    </span>
+                }
+                if !question.prompt.starts_with("Loading") {
+                    <details>
+                        <summary>{"Worked solution"}</summary>
+                        { solutions::view_solution(&solutions::worked_solution(&question)) }
+                    </details>
+                }
             </div>
         }
     } else {
@@ -748,6 +3380,17 @@ fn question_row(props: &QuestionRowProps) -> Html {
             <div class="question-header">
                 <div class="question-index">
                     {format!("Question {}", index + 1)}
+                    if question.attempts > 0 && question.is_correct.is_none() {
+                        <span class="attempt-note">{format!(" · try {}", question.attempts + 1)}</span>
+                    }
+                    if show_results && question.elapsed_ms > 0.0 {
+                        <span class="time-note">
+                            {format!(" · ⏱ {}", timing::format_secs(question.elapsed_ms))}
+                            if timing::slow_but_correct(&question) {
+                                {" 🐢 slow — not fluent yet"}
+                            }
+                        </span>
+                    }
                 </div>
                 <div class={classes!(
                     "question-tag",
@@ -761,13 +3404,25 @@ fn question_row(props: &QuestionRowProps) -> Html {
             </div>
             <div class="answer-row">
                 <input
+                    id={format!("answer-{index}")}
                     class="answer-input"
-                    type="number"
+                    type="text"
                     inputmode="numeric"
                     placeholder="Your answer"
                     value={question.user_answer.clone()}
                     oninput={on_answer_change}
+                    onfocus={on_focus}
+                    onblur={on_blur}
                 />
+                if !show_results && !question.prompt.starts_with("Loading") {
+                    <button
+                        class="btn-hint"
+                        disabled={hints_shown >= hints.len()}
+                        onclick={on_hint}
+                    >
+                        { if hints_shown == 0 { "Hint 💡".to_string() } else { format!("Hint {}/{} 💡", hints_shown, hints.len()) } }
+                    </button>
+                }
                 { if is_word {
                     html! {
                         <button class="btn-regen" onclick={on_regen_click}>
@@ -778,6 +3433,11 @@ fn question_row(props: &QuestionRowProps) -> Html {
                     Html::default()
                 }}
             </div>
+            if hints_shown > 0 {
+                <ol class="hint-list">
+                    { for hints.iter().take(hints_shown).map(|h| html! { <li>{h.clone()}</li> }) }
+                </ol>
+            }
             {feedback}
             {teacher_answer}
         </div>
@@ -787,4 +3447,185 @@ fn question_row(props: &QuestionRowProps) -> Html {
 // Trunk/Yew entrypoint
 fn main() {
     yew::Renderer::<App>::new().render();
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts(cfg: &QuizConfig) -> Vec<usize> {
+        question_type_counts(cfg).iter().map(|&(_, n)| n).collect()
+    }
+
+    #[test]
+    fn apportion_adds_up_for_uneven_weights() {
+        for total in MIN_QUESTIONS..=MAX_QUESTIONS {
+            for weights in [
+                vec![1, 1, 1],
+                vec![40, 40, 20],
+                vec![7, 3, 5, 11],
+                vec![100, 1],
+            ] {
+                let split = apportion(total, &weights);
+                assert_eq!(
+                    split.iter().sum::<usize>(),
+                    total,
+                    "{total} over {weights:?}"
+                );
+            }
+        }
+        assert_eq!(apportion(10, &[1, 1, 1]), [4, 3, 3]);
+        assert_eq!(apportion(10, &[50, 30, 20]), [5, 3, 2]);
+    }
+
+    #[test]
+    fn apportion_zero_weights() {
+        assert_eq!(apportion(10, &[0, 0]), [0, 0]);
+        assert_eq!(apportion(10, &[0, 3, 0, 2]), [0, 6, 0, 4]);
+    }
+
+    #[test]
+    fn percent_counts_fill_the_quiz() {
+        let mut cfg = QuizConfig {
+            include_mul: true,
+            include_div: true,
+            ..QuizConfig::default()
+        };
+        for n in MIN_QUESTIONS..=MAX_QUESTIONS {
+            cfg.num_questions = n;
+            assert_eq!(planned_question_total(&cfg), n);
+        }
+
+        // A zero share gets nothing; the rest still add up
+        cfg.num_questions = 10;
+        cfg.mix_mul = 0;
+        let split = counts(&cfg);
+        assert_eq!(split.iter().sum::<usize>(), 10);
+        assert_eq!(split[2], 0);
+
+        // All zero: spread evenly over the included types
+        cfg.mix_add = 0;
+        cfg.mix_sub = 0;
+        cfg.mix_div = 0;
+        cfg.mix_words = 0;
+        assert_eq!(counts(&cfg), [2, 2, 2, 2, 2]);
+    }
+
+    #[test]
+    fn every_nonzero_share_gets_a_question() {
+        let cfg = QuizConfig {
+            num_questions: 5,
+            include_mul: true,
+            mix_add: 90,
+            mix_sub: 4,
+            mix_mul: 3,
+            mix_words: 3,
+            ..QuizConfig::default()
+        };
+        assert!(counts(&cfg).iter().all(|&n| n >= 1));
+        assert_eq!(planned_question_total(&cfg), 5);
+    }
+
+    #[test]
+    fn exact_counts_and_their_bounds() {
+        let mut cfg = QuizConfig {
+            mix_mode: MixMode::Count,
+            mix_add: 3,
+            mix_sub: 4,
+            mix_words: 1,
+            ..QuizConfig::default()
+        };
+        assert_eq!(counts(&cfg), [3, 4, 1]);
+        assert!(mix_count_error(&cfg).is_none());
+
+        cfg.mix_add = 0;
+        cfg.mix_sub = 2;
+        assert!(mix_count_error(&cfg).is_some());
+        cfg.mix_add = 15;
+        cfg.mix_sub = 10;
+        assert!(mix_count_error(&cfg).is_some());
+        assert!(generate_questions_with_ai_placeholders(&cfg).is_err());
+    }
+
+    #[test]
+    fn switching_modes_keeps_the_quiz() {
+        let mut cfg = QuizConfig::default();
+        let before = counts(&cfg);
+        switch_mix_mode(&mut cfg, MixMode::Count);
+        assert_eq!(counts(&cfg), before);
+        assert_eq!(cfg.mix_mul, 0);
+
+        switch_mix_mode(&mut cfg, MixMode::Percent);
+        assert_eq!(counts(&cfg), before);
+        assert_eq!(cfg.mix_add + cfg.mix_sub + cfg.mix_words, 100);
+    }
+
+    #[test]
+    fn seeded_quiz_has_no_repeats_or_trivial_facts() {
+        for difficulty in [Difficulty::Easy, Difficulty::Moderate, Difficulty::Advanced] {
+            let cfg = QuizConfig {
+                num_questions: MAX_QUESTIONS,
+                difficulty,
+                include_mul: true,
+                include_div: true,
+                exclude_trivial: true,
+                ..QuizConfig::default()
+            };
+            for seed in 0..20 {
+                let qs = with_seed(seed, || generate_questions_with_ai_placeholders(&cfg)).unwrap();
+                assert_eq!(qs.len(), MAX_QUESTIONS);
+                let keys: Vec<_> = qs
+                    .iter()
+                    .filter_map(|q| q.fact.as_ref())
+                    .inspect(|f| assert!(!is_trivial_fact(f), "{}", fact_text(f)))
+                    .map(|f| fact_key(f, cfg.commuted_equal))
+                    .collect();
+                for (i, key) in keys.iter().enumerate() {
+                    assert!(!keys[..i].contains(key), "seed {seed} repeats a fact");
+                }
+                let again = with_seed(seed, || generate_questions_with_ai_placeholders(&cfg));
+                assert!(again.unwrap() == qs);
+            }
+        }
+    }
+
+    #[test]
+    fn small_range_is_used_up_exactly() {
+        // Level 1 addition without trivial facts: 1+2, 1+3, 1+4, 1+5, 2+3, ...
+        let cfg = QuizConfig {
+            exclude_trivial: true,
+            ..QuizConfig::default()
+        };
+        let available = acceptable_facts(&cfg, 1, BaseOp::Add).count();
+        assert_eq!(available, 10);
+        with_seed(7, || {
+            let mut used = Vec::new();
+            for _ in 0..available {
+                let fact = draw_fact(&cfg, 1, BaseOp::Add, &used).unwrap();
+                used.push(fact_key(&fact, cfg.commuted_equal));
+            }
+            assert!(draw_fact(&cfg, 1, BaseOp::Add, &used).is_none());
+        });
+    }
+
+    #[test]
+    fn given_facts_keep_the_mix() {
+        let cfg = QuizConfig::default();
+        // More due addition facts than addition's whole share
+        let due: Vec<Fact> = acceptable_facts(&cfg, 1, BaseOp::Add).take(8).collect();
+        for seed in 0..20 {
+            let qs = with_seed(seed, || generate_questions_starting_with(&cfg, &due)).unwrap();
+            assert_eq!(qs.len(), cfg.num_questions);
+            for (t, n) in question_type_counts(&cfg) {
+                let got = qs
+                    .iter()
+                    .filter(|q| match &q.fact {
+                        Some(f) => t == QuestionType::Basic(f.op),
+                        None => t == QuestionType::Word,
+                    })
+                    .count();
+                assert_eq!(got, n, "seed {seed}");
+            }
+            assert!(qs[0].fact == Some(due[0]));
+        }
+    }
+}
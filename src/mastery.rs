@@ -0,0 +1,199 @@
+//! Per-fact mastery: accuracy and response time for every basic fact a
+//! student has answered, plus the heatmap that shows them.
+
+use serde::{Deserialize, Serialize};
+use yew::prelude::*;
+
+use crate::storage::{load_json, save_json, student_key};
+use crate::timing::SLOW_MS;
+use crate::{fact_key, fact_text, BaseOp, Fact, Question};
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct FactStats {
+    pub fact: Fact,
+    pub attempts: u32,
+    pub correct: u32,
+    /// Attempts that have a usable response time
+    pub timed: u32,
+    pub total_ms: f64,
+}
+
+#[derive(Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct MasteryStats {
+    pub facts: Vec<FactStats>,
+}
+
+pub fn load_mastery(student: &str) -> MasteryStats {
+    load_json(&student_key("mastery", student)).unwrap_or_default()
+}
+
+pub fn save_mastery(student: &str, stats: &MasteryStats) {
+    save_json(&student_key("mastery", student), stats);
+}
+
+pub fn accuracy(s: &FactStats) -> f64 {
+    if s.attempts == 0 {
+        0.0
+    } else {
+        s.correct as f64 / s.attempts as f64
+    }
+}
+
+pub fn avg_ms(s: &FactStats) -> Option<f64> {
+    (s.timed > 0).then(|| s.total_ms / s.timed as f64)
+}
+
+pub fn find<'a>(stats: &'a MasteryStats, fact: &Fact) -> Option<&'a FactStats> {
+    let key = fact_key(fact, false);
+    stats.facts.iter().find(|s| fact_key(&s.fact, false) == key)
+}
+
+/// Add every graded basic question in `qs` to the stats
+pub fn record_questions(stats: &mut MasteryStats, qs: &[Question]) {
+    for q in qs {
+        let (Some(fact), Some(correct)) = (q.fact.as_ref(), q.is_correct) else {
+            continue;
+        };
+        let key = fact_key(fact, false);
+        let idx = match stats.facts.iter().position(|s| fact_key(&s.fact, false) == key) {
+            Some(idx) => idx,
+            None => {
+                stats.facts.push(FactStats {
+                    fact: *fact,
+                    attempts: 0,
+                    correct: 0,
+                    timed: 0,
+                    total_ms: 0.0,
+                });
+                stats.facts.len() - 1
+            }
+        };
+        let s = &mut stats.facts[idx];
+        s.attempts += 1;
+        if correct {
+            s.correct += 1;
+        }
+        if q.elapsed_ms > 0.0 {
+            s.timed += 1;
+            s.total_ms += q.elapsed_ms;
+        }
+    }
+}
+
+/// The `n` weakest tracked facts for an operation: lowest accuracy
+/// first, slowest first among equals
+pub fn weakest_facts(stats: &MasteryStats, op: BaseOp, n: usize) -> Vec<Fact> {
+    let mut facts: Vec<&FactStats> = stats.facts.iter().filter(|s| s.fact.op == op).collect();
+    facts.sort_by(|x, y| {
+        accuracy(x)
+            .partial_cmp(&accuracy(y))
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| {
+                let slow_x = avg_ms(x).unwrap_or(0.0);
+                let slow_y = avg_ms(y).unwrap_or(0.0);
+                slow_y.partial_cmp(&slow_x).unwrap_or(std::cmp::Ordering::Equal)
+            })
+    });
+    facts.iter().take(n).map(|s| s.fact).collect()
+}
+
+/// Cell color: hue from accuracy (red → green), paler when slow
+fn cell_style(s: &FactStats) -> String {
+    let hue = (accuracy(s) * 120.0).round();
+    let lightness = match avg_ms(s) {
+        Some(ms) if ms > SLOW_MS => 78,
+        _ => 58,
+    };
+    format!("background: hsl({hue}, 70%, {lightness}%);")
+}
+
+#[derive(Properties, PartialEq)]
+pub struct MasteryViewProps {
+    pub stats: MasteryStats,
+    /// Tapped a cell: start a quiz built around this fact
+    pub on_pick: Callback<Fact>,
+}
+
+#[function_component(MasteryView)]
+pub fn mastery_view(props: &MasteryViewProps) -> Html {
+    let op = use_state(|| BaseOp::Mul);
+    let max = match *op {
+        BaseOp::Add => 20,
+        _ => 12,
+    };
+
+    let pick_op = |target: BaseOp| {
+        let op = op.clone();
+        Callback::from(move |_| op.set(target))
+    };
+
+    let header = html! {
+        <tr>
+            <th>{crate::op_symbol(*op)}</th>
+            { for (0..=max).map(|b| html! { <th>{b}</th> }) }
+        </tr>
+    };
+
+    let rows = (0..=max).map(|a| {
+        let cells = (0..=max).map(|b| {
+            let fact = Fact { op: *op, a, b };
+            let on_pick = props.on_pick.clone();
+            let onclick = Callback::from(move |_| on_pick.emit(fact));
+            match find(&props.stats, &fact) {
+                Some(s) => {
+                    let time = avg_ms(s)
+                        .map(|ms| format!(", avg {:.1}s", ms / 1000.0))
+                        .unwrap_or_default();
+                    let title = format!(
+                        "{}: {}/{} correct{}",
+                        fact_text(&fact),
+                        s.correct,
+                        s.attempts,
+                        time
+                    );
+                    html! {
+                        <td class="heat-cell" style={cell_style(s)} {title} {onclick}></td>
+                    }
+                }
+                None => html! {
+                    <td class="heat-cell untried" title={fact_text(&fact)} {onclick}></td>
+                },
+            }
+        });
+        html! {
+            <tr>
+                <th>{a}</th>
+                { for cells }
+            </tr>
+        }
+    });
+
+    html! {
+        <div>
+            <div class="btn-row">
+                <button
+                    class={if *op == BaseOp::Mul { "btn-primary" } else { "btn-secondary" }}
+                    onclick={pick_op(BaseOp::Mul)}
+                >
+                    {"× facts (0–12)"}
+                </button>
+                <button
+                    class={if *op == BaseOp::Add { "btn-primary" } else { "btn-secondary" }}
+                    onclick={pick_op(BaseOp::Add)}
+                >
+                    {"+ facts (0–20)"}
+                </button>
+            </div>
+            <div class="heatmap-wrap">
+                <table class="heatmap">
+                    { header }
+                    { for rows }
+                </table>
+            </div>
+            <div class="tiny-note">
+                {"Green = always right, red = often missed, pale = right but slow. "}
+                {"Gray facts haven't been tried. Tap a fact to practice it with the weakest ones."}
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,396 @@
+//! Teacher PIN. Set the first time teacher mode is opened, kept only as a
+//! salted hash in localStorage, and asked for again whenever the unlock
+//! runs out. Keeps curious students away from the answers; it isn't a
+//! defence against someone clearing the site's data.
+
+use serde::{Deserialize, Serialize};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::storage::{app_key, load_json, remove_item, save_json};
+use crate::timing::{BrowserClock, Clock};
+use crate::{log, random_seed};
+
+/// How long teacher mode stays open after the PIN is entered
+pub const UNLOCK_MS: f64 = 10.0 * 60_000.0;
+
+/// Wrong guesses allowed before a pause
+const MAX_FAILURES: u32 = 5;
+const LOCKOUT_MS: f64 = 30_000.0;
+
+/// Hash rounds, so trying every 4-digit PIN against a copied hash is slow
+const ROUNDS: usize = 2_000;
+
+const PIN_DIGITS: std::ops::RangeInclusive<usize> = 4..=8;
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+struct PinRecord {
+    salt: String,
+    hash: String,
+}
+
+/// Wrong guesses so far and the pause they earned. Stored, so reloading
+/// the page doesn't hand out fresh guesses.
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+struct Lockout {
+    failures: u32,
+    blocked_until: Option<f64>,
+}
+
+fn load_lockout() -> Lockout {
+    load_json(&app_key("teacher-pin-lockout")).unwrap_or_default()
+}
+
+fn save_lockout(lockout: &Lockout) {
+    save_json(&app_key("teacher-pin-lockout"), lockout);
+}
+
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// SHA-256 of `data`
+fn sha256(data: &[u8]) -> [u8; 32] {
+    let mut h: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+    let mut msg = data.to_vec();
+    msg.push(0x80);
+    while msg.len() % 64 != 56 {
+        msg.push(0);
+    }
+    msg.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+
+    for block in msg.chunks(64) {
+        let mut w = [0u32; 64];
+        for (i, word) in block.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = h;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = hh
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(K[i])
+                .wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            hh = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+        for (state, v) in h.iter_mut().zip([a, b, c, d, e, f, g, hh]) {
+            *state = state.wrapping_add(v);
+        }
+    }
+
+    let mut out = [0u8; 32];
+    for (i, word) in h.iter().enumerate() {
+        out[i * 4..i * 4 + 4].copy_from_slice(&word.to_be_bytes());
+    }
+    out
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn hash_pin(salt: &str, pin: &str) -> String {
+    let mut digest = sha256(format!("{salt}:{pin}").as_bytes());
+    for _ in 1..ROUNDS {
+        let mut input = digest.to_vec();
+        input.extend_from_slice(salt.as_bytes());
+        digest = sha256(&input);
+    }
+    hex(&digest)
+}
+
+fn load_record() -> Option<PinRecord> {
+    load_json(&app_key("teacher-pin"))
+}
+
+pub fn has_pin() -> bool {
+    load_record().is_some()
+}
+
+fn set_pin(pin: &str) {
+    let salt = format!("{:016x}", random_seed());
+    let hash = hash_pin(&salt, pin);
+    save_json(&app_key("teacher-pin"), &PinRecord { salt, hash });
+}
+
+fn check_pin(pin: &str) -> bool {
+    match load_record() {
+        Some(record) => hash_pin(&record.salt, pin) == record.hash,
+        None => false,
+    }
+}
+
+/// A usable PIN, or why not
+fn validate_pin(pin: &str, confirm: &str) -> Result<(), String> {
+    if !PIN_DIGITS.contains(&pin.len()) || !pin.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!(
+            "Use {} to {} digits.",
+            PIN_DIGITS.start(),
+            PIN_DIGITS.end()
+        ));
+    }
+    if pin != confirm {
+        return Err("The two PINs don't match.".to_string());
+    }
+    Ok(())
+}
+
+/// "14:05" for an epoch-ms time, in local time
+pub fn clock_time(ms: f64) -> String {
+    let date = js_sys::Date::new(&ms.into());
+    format!("{}:{:02}", date.get_hours(), date.get_minutes())
+}
+
+#[derive(Properties, PartialEq)]
+pub struct TeacherLockProps {
+    /// When the current unlock runs out; `None` while locked
+    pub unlocked_until: Option<f64>,
+    pub on_unlock: Callback<()>,
+    pub on_lock: Callback<()>,
+    /// Button that asks for the PIN
+    #[prop_or(AttrValue::Static("Teacher mode"))]
+    pub action: AttrValue,
+    /// Shown next to the button while locked
+    #[prop_or(AttrValue::Static("🔒 Student mode – setup and answers are locked"))]
+    pub note: AttrValue,
+}
+
+#[function_component(TeacherLock)]
+pub fn teacher_lock(props: &TeacherLockProps) -> Html {
+    let open = use_state(|| false);
+    let pin = use_state(String::new);
+    let confirm = use_state(String::new);
+    let error = use_state(|| None::<String>);
+    let setting_up = !has_pin();
+
+    let on_open = {
+        let open = open.clone();
+        let error = error.clone();
+        Callback::from(move |_| {
+            error.set(None);
+            open.set(true);
+        })
+    };
+
+    let on_cancel = {
+        let open = open.clone();
+        let pin = pin.clone();
+        let confirm = confirm.clone();
+        Callback::from(move |_| {
+            pin.set(String::new());
+            confirm.set(String::new());
+            open.set(false);
+        })
+    };
+
+    let on_pin = {
+        let pin = pin.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            pin.set(input.value());
+        })
+    };
+
+    let on_confirm = {
+        let confirm = confirm.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            confirm.set(input.value());
+        })
+    };
+
+    let on_submit = {
+        let open = open.clone();
+        let pin = pin.clone();
+        let confirm = confirm.clone();
+        let error = error.clone();
+        let on_unlock = props.on_unlock.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let now = BrowserClock.now_ms();
+            if setting_up {
+                if let Err(msg) = validate_pin(pin.trim(), confirm.trim()) {
+                    error.set(Some(msg));
+                    return;
+                }
+                set_pin(pin.trim());
+                log("Teacher PIN set");
+            } else {
+                let lockout = load_lockout();
+                if let Some(until) = lockout.blocked_until {
+                    if now < until {
+                        error.set(Some(format!(
+                            "Too many wrong PINs. Try again in {} seconds.",
+                            ((until - now) / 1000.0).ceil()
+                        )));
+                        return;
+                    }
+                }
+                if !check_pin(pin.trim()) {
+                    let failures = lockout.failures + 1;
+                    save_lockout(&if failures >= MAX_FAILURES {
+                        Lockout {
+                            failures: 0,
+                            blocked_until: Some(now + LOCKOUT_MS),
+                        }
+                    } else {
+                        Lockout {
+                            failures,
+                            ..lockout
+                        }
+                    });
+                    pin.set(String::new());
+                    error.set(Some("That PIN isn't right.".to_string()));
+                    return;
+                }
+                remove_item(&app_key("teacher-pin-lockout"));
+            }
+            pin.set(String::new());
+            confirm.set(String::new());
+            error.set(None);
+            open.set(false);
+            on_unlock.emit(());
+        })
+    };
+
+    let on_lock = {
+        let on_lock = props.on_lock.clone();
+        Callback::from(move |_| on_lock.emit(()))
+    };
+
+    if let Some(until) = props.unlocked_until {
+        return html! {
+            <div class="teacher-lock">
+                <span>{format!("🔓 Teacher mode on – locks again at {}", clock_time(until))}</span>
+                <button class="btn-secondary" onclick={on_lock}>{"Lock now"}</button>
+            </div>
+        };
+    }
+
+    html! {
+        <div class="teacher-lock">
+            if !*open {
+                if !props.note.is_empty() {
+                    <span>{props.note.clone()}</span>
+                }
+                <button class="btn-secondary" onclick={on_open}>{props.action.clone()}</button>
+            } else {
+                <form class="pin-form" onsubmit={on_submit}>
+                    if setting_up {
+                        <span>{"Choose a teacher PIN (4–8 digits):"}</span>
+                    } else {
+                        <span>{"Teacher PIN:"}</span>
+                    }
+                    <input
+                        class="field-input pin-input"
+                        type="password"
+                        inputmode="numeric"
+                        autocomplete="off"
+                        value={(*pin).clone()}
+                        oninput={on_pin}
+                    />
+                    if setting_up {
+                        <span>{"Again:"}</span>
+                        <input
+                            class="field-input pin-input"
+                            type="password"
+                            inputmode="numeric"
+                            autocomplete="off"
+                            value={(*confirm).clone()}
+                            oninput={on_confirm}
+                        />
+                    }
+                    <button class="btn-primary" type="submit">
+                        {if setting_up { "Set PIN" } else { "Unlock" }}
+                    </button>
+                    <button class="btn-secondary" type="button" onclick={on_cancel}>{"Cancel"}</button>
+                </form>
+                if let Some(msg) = (*error).clone() {
+                    <div class="gen-error">{msg}</div>
+                }
+                if setting_up {
+                    <div class="tiny-note">
+                        {"The PIN is stored only on this device, as a hash. "}
+                        {"Teacher mode locks itself again after 10 minutes."}
+                    </div>
+                }
+            }
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // FIPS 180-2, appendix B.1, and the digest of no input at all
+    #[test]
+    fn sha256_known_digests() {
+        assert_eq!(
+            hex(&sha256(b"abc")),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            hex(&sha256(b"")),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    // Two-block message, FIPS 180-2 appendix B.2
+    #[test]
+    fn sha256_two_blocks() {
+        assert_eq!(
+            hex(&sha256(
+                b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"
+            )),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+        );
+    }
+
+    #[test]
+    fn pin_rules() {
+        assert!(validate_pin("1234", "1234").is_ok());
+        assert!(validate_pin("123", "123").is_err());
+        assert!(validate_pin("123456789", "123456789").is_err());
+        assert!(validate_pin("12a4", "12a4").is_err());
+        assert!(validate_pin("1234", "1235").is_err());
+    }
+
+    #[test]
+    fn hash_depends_on_salt_and_pin() {
+        let hash = hash_pin("salt", "1234");
+        assert_eq!(hash, hash_pin("salt", "1234"));
+        assert_ne!(hash, hash_pin("salt", "1235"));
+        assert_ne!(hash, hash_pin("pepper", "1234"));
+    }
+}
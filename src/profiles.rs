@@ -0,0 +1,520 @@
+//! Student profiles for shared devices. Each profile has its own name,
+//! avatar, grade and default quiz setup, and its id is the storage key
+//! for that student's boxes, mastery, history and trophies. Profiles can
+//! come from a class roster, with a quiz assigned to their whole group.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::storage::{app_key, load_json, remove_item, save_json, student_key};
+use crate::QuizConfig;
+
+/// Avatars a student can pick from
+pub const AVATARS: [&str; 16] = [
+    "🦊", "🐼", "🐸", "🐯", "🦄", "🐙", "🦖", "🐧", "🐝", "🦁", "🐢", "🐰", "🚀", "⚽", "🌈", "🎨",
+];
+
+/// Everything kept per student, removed with the profile
+const STUDENT_DATA: [&str; 4] = ["leitner", "mastery", "trophies", "history"];
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    pub id: String,
+    pub name: String,
+    pub avatar: String,
+    /// 0 = kindergarten
+    pub grade: u8,
+    /// Setup loaded when this student is picked
+    pub config: QuizConfig,
+    /// School's student ID, from the roster
+    #[serde(default)]
+    pub student_id: String,
+    /// Roster group, e.g. "Intervention"
+    #[serde(default)]
+    pub group: String,
+    /// `config` was assigned to this student, so it wins over the group's
+    #[serde(default)]
+    pub own_config: bool,
+}
+
+/// Quiz setup assigned to everyone in a roster group
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupAssignment {
+    pub group: String,
+    pub config: QuizConfig,
+}
+
+#[derive(Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct ProfileList {
+    pub profiles: Vec<Profile>,
+    /// Never reused, so a deleted student's data can't reappear
+    pub next_id: u32,
+    #[serde(default)]
+    pub groups: Vec<GroupAssignment>,
+}
+
+pub fn load_profiles() -> ProfileList {
+    let Some(mut raw) = load_json::<Value>(&app_key("profiles")) else {
+        return ProfileList::default();
+    };
+    migrate_own_config(&mut raw);
+    serde_json::from_value(raw).unwrap_or_default()
+}
+
+/// Profiles saved before group quizzes have no `own_config`. Any whose
+/// setup was changed from the default keeps it, so assigning a quiz to
+/// their group doesn't quietly replace what the teacher set by hand.
+fn migrate_own_config(raw: &mut Value) {
+    let Some(profiles) = raw.get_mut("profiles").and_then(Value::as_array_mut) else {
+        return;
+    };
+    for profile in profiles.iter_mut().filter_map(Value::as_object_mut) {
+        if profile.contains_key("own_config") {
+            continue;
+        }
+        let set_by_hand = profile
+            .get("config")
+            .and_then(|c| serde_json::from_value::<QuizConfig>(c.clone()).ok())
+            .is_some_and(|c| c != QuizConfig::default());
+        profile.insert("own_config".to_string(), set_by_hand.into());
+    }
+}
+
+pub fn save_profiles(list: &ProfileList) {
+    save_json(&app_key("profiles"), list);
+}
+
+pub fn grade_label(grade: u8) -> String {
+    match grade {
+        0 => "K".to_string(),
+        1 => "1st".to_string(),
+        2 => "2nd".to_string(),
+        3 => "3rd".to_string(),
+        g => format!("{g}th"),
+    }
+}
+
+pub fn add_profile(list: &mut ProfileList, name: &str, config: &QuizConfig) -> Profile {
+    list.next_id += 1;
+    let profile = Profile {
+        id: format!("profile-{}", list.next_id),
+        name: name.trim().to_string(),
+        avatar: AVATARS[(list.next_id as usize - 1) % AVATARS.len()].to_string(),
+        grade: 2,
+        config: config.clone(),
+        student_id: String::new(),
+        group: String::new(),
+        own_config: false,
+    };
+    list.profiles.push(profile.clone());
+    profile
+}
+
+/// Group names in roster order, each once
+pub fn group_names(list: &ProfileList) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for p in &list.profiles {
+        let group = p.group.trim();
+        if !group.is_empty() && !names.iter().any(|n| n.eq_ignore_ascii_case(group)) {
+            names.push(group.to_string());
+        }
+    }
+    names
+}
+
+pub fn group_config<'a>(list: &'a ProfileList, group: &str) -> Option<&'a QuizConfig> {
+    list.groups
+        .iter()
+        .find(|g| g.group.eq_ignore_ascii_case(group.trim()))
+        .map(|g| &g.config)
+}
+
+/// Move one student to `group`. When that empties their old group, its
+/// quiz goes along (the group was renamed) unless the new group already
+/// has one, so no assignment is left behind for a group nobody is in.
+pub fn set_group(list: &mut ProfileList, id: &str, group: &str) {
+    let group = group.trim().to_string();
+    let Some(profile) = list.profiles.iter_mut().find(|p| p.id == id) else {
+        return;
+    };
+    let old = std::mem::replace(&mut profile.group, group.clone());
+    let emptied = !list
+        .profiles
+        .iter()
+        .any(|p| p.group.eq_ignore_ascii_case(&old));
+    if emptied && !group.is_empty() && group_config(list, &group).is_none() {
+        if let Some(assignment) = list
+            .groups
+            .iter_mut()
+            .find(|g| g.group.eq_ignore_ascii_case(old.trim()))
+        {
+            assignment.group = group;
+        }
+    }
+    drop_empty_groups(list);
+}
+
+/// Forget quizzes assigned to groups nobody is in any more
+pub fn drop_empty_groups(list: &mut ProfileList) {
+    let names = group_names(list);
+    list.groups
+        .retain(|g| names.iter().any(|n| n.eq_ignore_ascii_case(g.group.trim())));
+}
+
+/// The quiz a student gets when they pick their name: their own
+/// assignment, else their group's, else their default setup
+pub fn assigned_config(list: &ProfileList, profile: &Profile) -> QuizConfig {
+    if !profile.own_config {
+        if let Some(config) = group_config(list, &profile.group) {
+            return config.clone();
+        }
+    }
+    profile.config.clone()
+}
+
+/// Drop the profile and everything stored under it
+pub fn delete_profile(list: &mut ProfileList, id: &str) {
+    list.profiles.retain(|p| p.id != id);
+    drop_empty_groups(list);
+    for kind in STUDENT_DATA {
+        remove_item(&student_key(kind, id));
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ProfilePickerProps {
+    pub profiles: Vec<Profile>,
+    /// `None` = play as guest
+    pub on_pick: Callback<Option<Profile>>,
+}
+
+#[function_component(ProfilePicker)]
+pub fn profile_picker(props: &ProfilePickerProps) -> Html {
+    let guest = {
+        let on_pick = props.on_pick.clone();
+        Callback::from(move |_| on_pick.emit(None))
+    };
+    html! {
+        <div class="profile-picker">
+            { for props.profiles.iter().map(|p| {
+                let on_pick = props.on_pick.clone();
+                let picked = p.clone();
+                html! {
+                    <button class="profile-tile" onclick={Callback::from(move |_| on_pick.emit(Some(picked.clone())))}>
+                        <span class="profile-avatar">{p.avatar.clone()}</span>
+                        <span>{p.name.clone()}</span>
+                        if !p.group.is_empty() {
+                            <span class="tiny-note">{p.group.clone()}</span>
+                        }
+                    </button>
+                }
+            }) }
+            <button class="profile-tile" onclick={guest}>
+                <span class="profile-avatar">{"👤"}</span>
+                <span>{"Guest"}</span>
+            </button>
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ProfileManagerProps {
+    pub list: ProfileList,
+    /// Setup on screen, saved as a student's default on request
+    pub current_config: QuizConfig,
+    pub on_change: Callback<ProfileList>,
+}
+
+#[function_component(ProfileManager)]
+pub fn profile_manager(props: &ProfileManagerProps) -> Html {
+    let new_name = use_state(String::new);
+
+    let on_new_name = {
+        let new_name = new_name.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            new_name.set(input.value());
+        })
+    };
+
+    let on_add = {
+        let new_name = new_name.clone();
+        let list = props.list.clone();
+        let config = props.current_config.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |_| {
+            if new_name.trim().is_empty() {
+                return;
+            }
+            let mut list = list.clone();
+            add_profile(&mut list, &new_name, &config);
+            on_change.emit(list);
+            new_name.set(String::new());
+        })
+    };
+
+    // Apply `edit` to one profile and hand the new list up
+    let update = |id: &str, edit: fn(&mut Profile, String)| {
+        let list = props.list.clone();
+        let on_change = props.on_change.clone();
+        let id = id.to_string();
+        move |value: String| {
+            let mut list = list.clone();
+            if let Some(p) = list.profiles.iter_mut().find(|p| p.id == id) {
+                edit(p, value);
+            }
+            on_change.emit(list);
+        }
+    };
+
+    html! {
+        <div>
+            <table class="results-table">
+                <tr>
+                    <th>{"Avatar"}</th>
+                    <th>{"Name"}</th>
+                    <th>{"Grade"}</th>
+                    <th>{"ID"}</th>
+                    <th>{"Group"}</th>
+                    <th>{"Quiz"}</th>
+                    <th></th>
+                </tr>
+                { for props.list.profiles.iter().map(|p| {
+                    let set_avatar = update(&p.id, |p, v| p.avatar = v);
+                    let set_name = update(&p.id, |p, v| p.name = v);
+                    let set_grade = update(&p.id, |p, v| p.grade = v.parse().unwrap_or(p.grade));
+                    let set_student_id = update(&p.id, |p, v| p.student_id = v.trim().to_string());
+                    let change_group = {
+                        let list = props.list.clone();
+                        let on_change = props.on_change.clone();
+                        let id = p.id.clone();
+                        move |group: String| {
+                            let mut list = list.clone();
+                            set_group(&mut list, &id, &group);
+                            on_change.emit(list);
+                        }
+                    };
+                    let use_group = update(&p.id, |p, _| p.own_config = false);
+                    let group_assigned = !p.own_config && group_config(&props.list, &p.group).is_some();
+                    let on_save_config = {
+                        let list = props.list.clone();
+                        let config = props.current_config.clone();
+                        let on_change = props.on_change.clone();
+                        let id = p.id.clone();
+                        Callback::from(move |_| {
+                            let mut list = list.clone();
+                            if let Some(p) = list.profiles.iter_mut().find(|p| p.id == id) {
+                                p.config = config.clone();
+                                p.own_config = true;
+                            }
+                            on_change.emit(list);
+                        })
+                    };
+                    let on_delete = {
+                        let list = props.list.clone();
+                        let on_change = props.on_change.clone();
+                        let id = p.id.clone();
+                        let name = p.name.clone();
+                        Callback::from(move |_| {
+                            let sure = web_sys::window()
+                                .and_then(|w| {
+                                    w.confirm_with_message(&format!(
+                                        "Delete {name} and all of their progress?"
+                                    ))
+                                    .ok()
+                                })
+                                .unwrap_or(false);
+                            if sure {
+                                let mut list = list.clone();
+                                delete_profile(&mut list, &id);
+                                on_change.emit(list);
+                            }
+                        })
+                    };
+                    html! {
+                        <tr>
+                            <td>
+                                <select
+                                    class="field-input"
+                                    onchange={Callback::from(move |e: Event| {
+                                        let select: HtmlInputElement = e.target_unchecked_into();
+                                        set_avatar(select.value());
+                                    })}
+                                >
+                                    { for AVATARS.iter().map(|a| html! {
+                                        <option value={*a} selected={p.avatar == *a}>{*a}</option>
+                                    }) }
+                                </select>
+                            </td>
+                            <td>
+                                <input
+                                    class="field-input"
+                                    type="text"
+                                    value={p.name.clone()}
+                                    onchange={Callback::from(move |e: Event| {
+                                        let input: HtmlInputElement = e.target_unchecked_into();
+                                        set_name(input.value());
+                                    })}
+                                />
+                            </td>
+                            <td>
+                                <select
+                                    class="field-input"
+                                    onchange={Callback::from(move |e: Event| {
+                                        let select: HtmlInputElement = e.target_unchecked_into();
+                                        set_grade(select.value());
+                                    })}
+                                >
+                                    { for (0..=5u8).map(|g| html! {
+                                        <option value={g.to_string()} selected={p.grade == g}>{grade_label(g)}</option>
+                                    }) }
+                                </select>
+                            </td>
+                            <td>
+                                <input
+                                    class="field-input"
+                                    type="text"
+                                    value={p.student_id.clone()}
+                                    onchange={Callback::from(move |e: Event| {
+                                        let input: HtmlInputElement = e.target_unchecked_into();
+                                        set_student_id(input.value());
+                                    })}
+                                />
+                            </td>
+                            <td>
+                                <input
+                                    class="field-input"
+                                    type="text"
+                                    value={p.group.clone()}
+                                    onchange={Callback::from(move |e: Event| {
+                                        let input: HtmlInputElement = e.target_unchecked_into();
+                                        change_group(input.value());
+                                    })}
+                                />
+                            </td>
+                            <td>
+                                <div class="tiny-note">
+                                    {if group_assigned {
+                                        format!("{}'s quiz", p.group)
+                                    } else if p.own_config {
+                                        "Own quiz".to_string()
+                                    } else {
+                                        "Default quiz".to_string()
+                                    }}
+                                </div>
+                                <button class="btn-secondary" onclick={on_save_config}>
+                                    {"Use current setup"}
+                                </button>
+                                if p.own_config && group_config(&props.list, &p.group).is_some() {
+                                    <button
+                                        class="btn-secondary"
+                                        onclick={Callback::from(move |_| use_group(String::new()))}
+                                    >
+                                        {"Use group's quiz"}
+                                    </button>
+                                }
+                            </td>
+                            <td>
+                                <button class="btn-secondary" onclick={on_delete}>{"Delete"}</button>
+                            </td>
+                        </tr>
+                    }
+                }) }
+            </table>
+            <div class="btn-row">
+                <input
+                    class="field-input"
+                    type="text"
+                    placeholder="New student's first name"
+                    value={(*new_name).clone()}
+                    oninput={on_new_name}
+                />
+                <button class="btn-primary" onclick={on_add}>{"Add student"}</button>
+            </div>
+            <div class="tiny-note">
+                {"New students start with the setup above as their default quiz. "}
+                {"“Use current setup” assigns it to that student ahead of their group's quiz."}
+            </div>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assign(list: &mut ProfileList, group: &str) {
+        list.groups.push(GroupAssignment {
+            group: group.to_string(),
+            config: QuizConfig {
+                num_questions: 5,
+                ..QuizConfig::default()
+            },
+        });
+    }
+
+    #[test]
+    fn old_profiles_keep_a_hand_set_quiz() {
+        let mut list = ProfileList::default();
+        add_profile(&mut list, "Ana", &QuizConfig::default());
+        add_profile(
+            &mut list,
+            "Ben",
+            &QuizConfig {
+                num_questions: 15,
+                ..QuizConfig::default()
+            },
+        );
+        let mut raw = serde_json::to_value(&list).unwrap();
+        for p in raw["profiles"].as_array_mut().unwrap() {
+            p.as_object_mut().unwrap().remove("own_config");
+        }
+        migrate_own_config(&mut raw);
+        let list: ProfileList = serde_json::from_value(raw).unwrap();
+        assert!(!list.profiles[0].own_config);
+        assert!(list.profiles[1].own_config);
+    }
+
+    #[test]
+    fn renaming_a_group_moves_its_quiz() {
+        let mut list = ProfileList::default();
+        let ana = add_profile(&mut list, "Ana", &QuizConfig::default());
+        let ben = add_profile(&mut list, "Ben", &QuizConfig::default());
+        set_group(&mut list, &ana.id, "Blue");
+        set_group(&mut list, &ben.id, "Blue");
+        assign(&mut list, "Blue");
+
+        // Half moved: the quiz stays with the students still in Blue
+        set_group(&mut list, &ana.id, "Bluebirds");
+        assert!(group_config(&list, "Blue").is_some());
+        assert!(group_config(&list, "Bluebirds").is_none());
+
+        // Last one out renames the group
+        set_group(&mut list, &ben.id, "Bluebirds");
+        assert!(group_config(&list, "Blue").is_none());
+        assert_eq!(group_config(&list, "Bluebirds").unwrap().num_questions, 5);
+        assert_eq!(list.groups.len(), 1);
+    }
+
+    #[test]
+    fn emptied_group_loses_its_quiz() {
+        let mut list = ProfileList::default();
+        let ana = add_profile(&mut list, "Ana", &QuizConfig::default());
+        let ben = add_profile(&mut list, "Ben", &QuizConfig::default());
+        set_group(&mut list, &ana.id, "Blue");
+        set_group(&mut list, &ben.id, "Red");
+        assign(&mut list, "Blue");
+        assign(&mut list, "Red");
+
+        // Red already has a quiz, so Blue's is dropped, not merged
+        set_group(&mut list, &ana.id, "Red");
+        assert!(group_config(&list, "Blue").is_none());
+        assert_eq!(list.groups.len(), 1);
+
+        set_group(&mut list, &ana.id, "");
+        set_group(&mut list, &ben.id, "");
+        assert!(list.groups.is_empty());
+    }
+}
@@ -0,0 +1,617 @@
+//! QR Code encoder (model 2, byte mode), so quiz links can be scanned
+//! without typing and without any network service. Follows ISO/IEC 18004:
+//! the smallest version at level L that fits, bumped to M when that fits
+//! in the same size, and the mask with the lowest penalty score.
+
+use yew::prelude::*;
+
+/// Error correction levels we encode with
+#[derive(Clone, Copy, PartialEq)]
+enum Ecc {
+    Low,
+    Medium,
+}
+
+impl Ecc {
+    fn format_bits(self) -> u32 {
+        match self {
+            Ecc::Low => 1,
+            Ecc::Medium => 0,
+        }
+    }
+
+    fn row(self) -> usize {
+        match self {
+            Ecc::Low => 0,
+            Ecc::Medium => 1,
+        }
+    }
+}
+
+// Indexed by [level][version]; version 0 is unused
+const ECC_CODEWORDS_PER_BLOCK: [[u8; 41]; 2] = [
+    [
+        0, 7, 10, 15, 20, 26, 18, 20, 24, 30, 18, 20, 24, 26, 30, 22, 24, 28, 30, 28, 28, 28, 28,
+        30, 30, 26, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+    [
+        0, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26, 30, 22, 22, 24, 24, 28, 28, 26, 26, 26, 26, 28,
+        28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28,
+    ],
+];
+
+const NUM_ERROR_CORRECTION_BLOCKS: [[u8; 41]; 2] = [
+    [
+        0, 1, 1, 1, 1, 1, 2, 2, 2, 2, 4, 4, 4, 4, 4, 6, 6, 6, 6, 7, 8, 8, 9, 9, 10, 12, 12, 12, 13,
+        14, 15, 16, 17, 18, 19, 19, 20, 21, 22, 24, 25,
+    ],
+    [
+        0, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5, 5, 8, 9, 9, 10, 10, 11, 13, 14, 16, 17, 17, 18, 20, 21,
+        23, 25, 26, 28, 29, 31, 33, 35, 37, 38, 40, 43, 45, 47, 49,
+    ],
+];
+
+const MAX_VERSION: usize = 40;
+
+pub struct QrCode {
+    size: usize,
+    /// Row-major, `true` = dark
+    modules: Vec<bool>,
+    /// Finder, timing, alignment, format and version areas
+    function: Vec<bool>,
+}
+
+/// Modules left for data and error correction once function patterns are drawn
+fn raw_data_modules(ver: usize) -> usize {
+    let mut result = (16 * ver + 128) * ver + 64;
+    if ver >= 2 {
+        let num_align = ver / 7 + 2;
+        result -= (25 * num_align - 10) * num_align - 55;
+        if ver >= 7 {
+            result -= 36;
+        }
+    }
+    result
+}
+
+fn data_codewords(ver: usize, ecc: Ecc) -> usize {
+    raw_data_modules(ver) / 8
+        - ECC_CODEWORDS_PER_BLOCK[ecc.row()][ver] as usize
+            * NUM_ERROR_CORRECTION_BLOCKS[ecc.row()][ver] as usize
+}
+
+/// Bits needed for one byte-mode segment of `len` bytes
+fn segment_bits(ver: usize, len: usize) -> usize {
+    let count_bits = if ver <= 9 { 8 } else { 16 };
+    4 + count_bits + 8 * len
+}
+
+// --- Reed–Solomon over GF(2^8), polynomial 0x11D ---
+
+fn gf_mul(x: u8, y: u8) -> u8 {
+    let mut z: u32 = 0;
+    for i in (0..8).rev() {
+        z = (z << 1) ^ ((z >> 7) * 0x11D);
+        z ^= ((y as u32 >> i) & 1) * x as u32;
+    }
+    z as u8
+}
+
+fn rs_divisor(degree: usize) -> Vec<u8> {
+    let mut result = vec![0u8; degree];
+    result[degree - 1] = 1;
+    let mut root = 1u8;
+    for _ in 0..degree {
+        for j in 0..degree {
+            result[j] = gf_mul(result[j], root);
+            if j + 1 < degree {
+                result[j] ^= result[j + 1];
+            }
+        }
+        root = gf_mul(root, 0x02);
+    }
+    result
+}
+
+fn rs_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut result = vec![0u8; divisor.len()];
+    for &b in data {
+        let factor = b ^ result.remove(0);
+        result.push(0);
+        for (r, &d) in result.iter_mut().zip(divisor) {
+            *r ^= gf_mul(d, factor);
+        }
+    }
+    result
+}
+
+/// Split data into blocks, add error correction, interleave
+fn add_ecc_and_interleave(data: &[u8], ver: usize, ecc: Ecc) -> Vec<u8> {
+    let num_blocks = NUM_ERROR_CORRECTION_BLOCKS[ecc.row()][ver] as usize;
+    let ecc_len = ECC_CODEWORDS_PER_BLOCK[ecc.row()][ver] as usize;
+    let raw_codewords = raw_data_modules(ver) / 8;
+    let num_short = num_blocks - raw_codewords % num_blocks;
+    let short_len = raw_codewords / num_blocks;
+
+    let divisor = rs_divisor(ecc_len);
+    let mut blocks: Vec<(&[u8], Vec<u8>)> = Vec::with_capacity(num_blocks);
+    let mut k = 0;
+    for i in 0..num_blocks {
+        let len = short_len - ecc_len + usize::from(i >= num_short);
+        let block = &data[k..k + len];
+        k += len;
+        blocks.push((block, rs_remainder(block, &divisor)));
+    }
+
+    let mut result = Vec::with_capacity(raw_codewords);
+    for i in 0..=short_len - ecc_len {
+        for (block, _) in &blocks {
+            if let Some(&b) = block.get(i) {
+                result.push(b);
+            }
+        }
+    }
+    for i in 0..ecc_len {
+        for (_, ecc) in &blocks {
+            result.push(ecc[i]);
+        }
+    }
+    result
+}
+
+/// 15-bit format information: level and mask, BCH-protected and masked
+fn format_bits(ecc: Ecc, mask: u8) -> u32 {
+    let data = ecc.format_bits() << 3 | mask as u32;
+    let mut rem = data;
+    for _ in 0..10 {
+        rem = (rem << 1) ^ ((rem >> 9) * 0x537);
+    }
+    (data << 10 | rem) ^ 0x5412
+}
+
+/// 18-bit version information, drawn from version 7 up
+fn version_bits(ver: usize) -> u32 {
+    let mut rem = ver as u32;
+    for _ in 0..12 {
+        rem = (rem << 1) ^ ((rem >> 11) * 0x1F25);
+    }
+    (ver as u32) << 12 | rem
+}
+
+fn alignment_positions(ver: usize) -> Vec<usize> {
+    if ver == 1 {
+        return Vec::new();
+    }
+    let num_align = ver / 7 + 2;
+    let size = ver * 4 + 17;
+    let step = if ver == 32 {
+        26
+    } else {
+        (ver * 4 + num_align * 2 + 1) / (num_align * 2 - 2) * 2
+    };
+    let mut result: Vec<usize> = (0..num_align - 1).map(|i| size - 7 - i * step).collect();
+    result.push(6);
+    result.reverse();
+    result
+}
+
+impl QrCode {
+    /// Encode `text` as bytes, or explain why it can't be
+    pub fn encode(text: &str) -> Result<QrCode, String> {
+        let bytes = text.as_bytes();
+        let ver = (1..=MAX_VERSION)
+            .find(|&v| segment_bits(v, bytes.len()) <= data_codewords(v, Ecc::Low) * 8)
+            .ok_or_else(|| {
+                format!(
+                    "This link is too long for a QR code ({} characters).",
+                    bytes.len()
+                )
+            })?;
+        let ecc = if segment_bits(ver, bytes.len()) <= data_codewords(ver, Ecc::Medium) * 8 {
+            Ecc::Medium
+        } else {
+            Ecc::Low
+        };
+
+        // Mode indicator, character count, data, terminator, padding
+        let capacity_bits = data_codewords(ver, ecc) * 8;
+        let mut bits: Vec<bool> = Vec::with_capacity(capacity_bits);
+        let mut push = |value: u32, len: usize| {
+            for i in (0..len).rev() {
+                bits.push((value >> i) & 1 == 1);
+            }
+        };
+        push(0b0100, 4);
+        push(bytes.len() as u32, if ver <= 9 { 8 } else { 16 });
+        for &b in bytes {
+            push(b as u32, 8);
+        }
+        let terminator = (capacity_bits - bits.len()).min(4);
+        bits.extend(std::iter::repeat_n(false, terminator));
+        bits.extend(std::iter::repeat_n(false, (8 - bits.len() % 8) % 8));
+        let mut data: Vec<u8> = bits
+            .chunks(8)
+            .map(|byte| byte.iter().fold(0u8, |acc, &b| acc << 1 | u8::from(b)))
+            .collect();
+        for pad in [0xEC, 0x11].into_iter().cycle() {
+            if data.len() >= capacity_bits / 8 {
+                break;
+            }
+            data.push(pad);
+        }
+
+        let codewords = add_ecc_and_interleave(&data, ver, ecc);
+        let size = ver * 4 + 17;
+        let mut qr = QrCode {
+            size,
+            modules: vec![false; size * size],
+            function: vec![false; size * size],
+        };
+        qr.draw_function_patterns(ver, ecc);
+        qr.draw_codewords(&codewords);
+
+        let mut best: Option<(u32, u8)> = None;
+        for mask in 0..8u8 {
+            qr.apply_mask(mask);
+            qr.draw_format_bits(ecc, mask);
+            let penalty = qr.penalty();
+            if best.is_none_or(|(p, _)| penalty < p) {
+                best = Some((penalty, mask));
+            }
+            qr.apply_mask(mask); // masking is its own inverse
+        }
+        let mask = best.map(|(_, m)| m).unwrap_or(0);
+        qr.apply_mask(mask);
+        qr.draw_format_bits(ecc, mask);
+        Ok(qr)
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_dark(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.size + x]
+    }
+
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        self.modules[y * self.size + x] = dark;
+        self.function[y * self.size + x] = true;
+    }
+
+    fn draw_function_patterns(&mut self, ver: usize, ecc: Ecc) {
+        let size = self.size;
+        for i in 0..size {
+            self.set_function(6, i, i % 2 == 0);
+            self.set_function(i, 6, i % 2 == 0);
+        }
+        self.draw_finder(3, 3);
+        self.draw_finder(size - 4, 3);
+        self.draw_finder(3, size - 4);
+
+        let align = alignment_positions(ver);
+        let n = align.len();
+        for (i, &x) in align.iter().enumerate() {
+            for (j, &y) in align.iter().enumerate() {
+                let on_finder =
+                    (i == 0 && j == 0) || (i == 0 && j == n - 1) || (i == n - 1 && j == 0);
+                if !on_finder {
+                    self.draw_alignment(x, y);
+                }
+            }
+        }
+
+        // Reserve the format areas now; real bits go in once the mask is chosen
+        self.draw_format_bits(ecc, 0);
+        self.draw_version(ver);
+    }
+
+    fn draw_finder(&mut self, cx: usize, cy: usize) {
+        for dy in -4i32..=4 {
+            for dx in -4i32..=4 {
+                let (x, y) = (cx as i32 + dx, cy as i32 + dy);
+                if (0..self.size as i32).contains(&x) && (0..self.size as i32).contains(&y) {
+                    let dist = dx.abs().max(dy.abs());
+                    self.set_function(x as usize, y as usize, dist != 2 && dist != 4);
+                }
+            }
+        }
+    }
+
+    fn draw_alignment(&mut self, cx: usize, cy: usize) {
+        for dy in -2i32..=2 {
+            for dx in -2i32..=2 {
+                let x = (cx as i32 + dx) as usize;
+                let y = (cy as i32 + dy) as usize;
+                self.set_function(x, y, dx.abs().max(dy.abs()) != 1);
+            }
+        }
+    }
+
+    fn draw_format_bits(&mut self, ecc: Ecc, mask: u8) {
+        let bits = format_bits(ecc, mask);
+        let bit = |i: usize| (bits >> i) & 1 == 1;
+        let size = self.size;
+
+        for i in 0..=5 {
+            self.set_function(8, i, bit(i));
+        }
+        self.set_function(8, 7, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(7, 8, bit(8));
+        for i in 9..15 {
+            self.set_function(14 - i, 8, bit(i));
+        }
+
+        for i in 0..8 {
+            self.set_function(size - 1 - i, 8, bit(i));
+        }
+        for i in 8..15 {
+            self.set_function(8, size - 15 + i, bit(i));
+        }
+        self.set_function(8, size - 8, true);
+    }
+
+    fn draw_version(&mut self, ver: usize) {
+        if ver < 7 {
+            return;
+        }
+        let bits = version_bits(ver);
+        for i in 0..18 {
+            let dark = (bits >> i) & 1 == 1;
+            let a = self.size - 11 + i % 3;
+            let b = i / 3;
+            self.set_function(a, b, dark);
+            self.set_function(b, a, dark);
+        }
+    }
+
+    /// Zig-zag the codeword bits through every non-function module
+    fn draw_codewords(&mut self, data: &[u8]) {
+        let size = self.size;
+        let total_bits = data.len() * 8;
+        let mut i = 0;
+        let mut right = size as i32 - 1;
+        while right >= 1 {
+            if right == 6 {
+                right = 5;
+            }
+            for vert in 0..size {
+                for j in 0..2 {
+                    let x = right as usize - j;
+                    let upward = (right + 1) & 2 == 0;
+                    let y = if upward { size - 1 - vert } else { vert };
+                    if !self.function[y * size + x] && i < total_bits {
+                        self.modules[y * size + x] = (data[i >> 3] >> (7 - (i & 7))) & 1 == 1;
+                        i += 1;
+                    }
+                }
+            }
+            right -= 2;
+        }
+    }
+
+    fn apply_mask(&mut self, mask: u8) {
+        let size = self.size;
+        for y in 0..size {
+            for x in 0..size {
+                let invert = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+                let idx = y * size + x;
+                if invert && !self.function[idx] {
+                    self.modules[idx] = !self.modules[idx];
+                }
+            }
+        }
+    }
+
+    /// Penalty score from the spec: long runs, 2×2 blocks, finder look-alikes, imbalance
+    fn penalty(&self) -> u32 {
+        let size = self.size;
+        let mut total = 0u32;
+
+        let line_penalty = |line: &[bool]| -> u32 {
+            let mut p = 0;
+            let mut run = 1;
+            for i in 1..line.len() {
+                if line[i] == line[i - 1] {
+                    run += 1;
+                } else {
+                    if run >= 5 {
+                        p += 3 + (run - 5);
+                    }
+                    run = 1;
+                }
+            }
+            if run >= 5 {
+                p += 3 + (run - 5);
+            }
+            // 1:1:3:1:1 dark pattern with four light modules on one side
+            // (outside the symbol counts as light)
+            let get = |i: i32| i >= 0 && (i as usize) < line.len() && line[i as usize];
+            const CORE: [bool; 7] = [true, false, true, true, true, false, true];
+            for start in -4..line.len() as i32 {
+                let core = (0..7).all(|k| get(start + k) == CORE[k as usize]);
+                if core {
+                    let before = (1..=4).all(|k| !get(start - k));
+                    let after = (0..4).all(|k| !get(start + 7 + k));
+                    if before || after {
+                        p += 40;
+                    }
+                }
+            }
+            p
+        };
+
+        for y in 0..size {
+            let row: Vec<bool> = (0..size).map(|x| self.is_dark(x, y)).collect();
+            total += line_penalty(&row);
+        }
+        for x in 0..size {
+            let col: Vec<bool> = (0..size).map(|y| self.is_dark(x, y)).collect();
+            total += line_penalty(&col);
+        }
+
+        for y in 0..size - 1 {
+            for x in 0..size - 1 {
+                let c = self.is_dark(x, y);
+                if c == self.is_dark(x + 1, y)
+                    && c == self.is_dark(x, y + 1)
+                    && c == self.is_dark(x + 1, y + 1)
+                {
+                    total += 3;
+                }
+            }
+        }
+
+        let dark = self.modules.iter().filter(|&&m| m).count();
+        let cells = size * size;
+        let k = (dark * 20)
+            .abs_diff(cells * 10)
+            .div_ceil(cells)
+            .saturating_sub(1);
+        total + k as u32 * 10
+    }
+
+    /// SVG path covering every dark module, offset by a quiet-zone `border`
+    pub fn svg_path(&self, border: usize) -> String {
+        let mut d = String::new();
+        for y in 0..self.size {
+            for x in 0..self.size {
+                if self.is_dark(x, y) {
+                    d.push_str(&format!("M{},{}h1v1h-1z", x + border, y + border));
+                }
+            }
+        }
+        d
+    }
+}
+
+/// Quiet zone around the symbol, in modules (the spec asks for 4)
+const BORDER: usize = 4;
+
+/// Smallest module that still scans from paper or across a classroom;
+/// bigger symbols grow past their box's CSS size to keep it
+const MIN_MODULE_MM: f64 = 0.5;
+
+#[derive(Properties, PartialEq)]
+pub struct QrViewProps {
+    pub text: AttrValue,
+    #[prop_or_default]
+    pub class: Classes,
+}
+
+#[function_component(QrView)]
+pub fn qr_view(props: &QrViewProps) -> Html {
+    let text = props.text.clone();
+    let code = use_memo(text, |text| QrCode::encode(text));
+    match &*code {
+        Ok(qr) => {
+            let dim = qr.size() + BORDER * 2;
+            let min_mm = dim as f64 * MIN_MODULE_MM;
+            html! {
+                <svg
+                    class={classes!("qr-code", props.class.clone())}
+                    style={format!("min-width: {min_mm}mm; min-height: {min_mm}mm;")}
+                    viewBox={format!("0 0 {dim} {dim}")}
+                    shape-rendering="crispEdges"
+                    role="img"
+                    aria-label="QR code for the quiz link"
+                >
+                    <rect width="100%" height="100%" fill="#fff" />
+                    <path d={qr.svg_path(BORDER)} fill="#000" />
+                </svg>
+            }
+        }
+        Err(msg) => html! { <div class="gen-error">{msg.clone()}</div> },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(qr: &QrCode) -> Vec<String> {
+        (0..qr.size())
+            .map(|y| {
+                (0..qr.size())
+                    .map(|x| if qr.is_dark(x, y) { '#' } else { '.' })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// "HELLO WORLD" in byte mode, version 1-M, mask 3, as drawn by an
+    /// independent encoder (Kazuhiko Arase's QRCode)
+    const HELLO_WORLD: [&str; 21] = [
+        "#######.#...#.#######",
+        "#.....#.#...#.#.....#",
+        "#.###.#.......#.###.#",
+        "#.###.#.#.#.#.#.###.#",
+        "#.###.#..###..#.###.#",
+        "#.....#...###.#.....#",
+        "#######.#.#.#.#######",
+        "........#####........",
+        "#.##.###.#.##.#..#.##",
+        ".##....#.#######.##..",
+        ".....#####.#.#.#...##",
+        "#.#.##.##..#...#.#.#.",
+        "#...#.##.##.##....#.#",
+        "........#.##..##..#.#",
+        "#######.#.#######....",
+        "#.....#.###..#.#.####",
+        "#.###.#..#..#.#..#...",
+        "#.###.#.###...#..###.",
+        "#.###.#.##..#..#..#..",
+        "#.....#..###.####...#",
+        "#######.##.#.#.#.....",
+    ];
+
+    #[test]
+    fn matches_reference_symbol() {
+        let qr = QrCode::encode("HELLO WORLD").unwrap();
+        assert_eq!(rows(&qr), HELLO_WORLD);
+    }
+
+    #[test]
+    fn format_bits_match_the_spec() {
+        assert_eq!(format_bits(Ecc::Low, 0), 0b111011111000100);
+        assert_eq!(format_bits(Ecc::Medium, 0), 0b101010000010010);
+        // Worked example in ISO/IEC 18004 Annex C
+        assert_eq!(format_bits(Ecc::Medium, 5), 0b100000011001110);
+    }
+
+    #[test]
+    fn version_bits_match_the_spec() {
+        assert_eq!(version_bits(7), 0b000111110010010100);
+        assert_eq!(version_bits(8), 0b001000010110111100);
+        assert_eq!(version_bits(40), 0b101000110001101001);
+    }
+
+    #[test]
+    fn version_block_is_drawn() {
+        let text = "x".repeat(150);
+        let qr = QrCode::encode(&text).unwrap();
+        assert_eq!(qr.size(), 7 * 4 + 17);
+        let bits = version_bits(7);
+        for i in 0..18 {
+            let dark = (bits >> i) & 1 == 1;
+            let (a, b) = (qr.size() - 11 + i % 3, i / 3);
+            assert_eq!(qr.is_dark(a, b), dark, "top-right bit {i}");
+            assert_eq!(qr.is_dark(b, a), dark, "bottom-left bit {i}");
+        }
+    }
+
+    #[test]
+    fn too_long_is_an_error() {
+        assert!(QrCode::encode(&"x".repeat(3000)).is_err());
+    }
+}
@@ -0,0 +1,177 @@
+//! Saved quizzes: a versioned JSON document holding the setup and every
+//! question, word-problem text included, so a good quiz can be reused
+//! without asking the Worker again.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{fact_text, BaseOp, Fact, Question, QuizConfig};
+
+/// Marks our files apart from any other JSON
+const FORMAT: &str = "math-quest-quiz";
+
+/// Bump when the document layout changes; older files must keep loading
+pub const VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct QuizFile {
+    format: String,
+    version: u32,
+    /// Epoch milliseconds
+    saved_ms: f64,
+    config: QuizConfig,
+    questions: Vec<Question>,
+}
+
+/// The question as a fresh copy: no answer, grade, timing or hints
+fn blank_question(q: &Question) -> Question {
+    Question {
+        user_answer: String::new(),
+        is_correct: None,
+        unanswered: None,
+        elapsed_ms: 0.0,
+        focus_started: None,
+        hints_used: 0,
+        attempts: 0,
+        first_try_correct: None,
+        ..q.clone()
+    }
+}
+
+/// The answer to a fact read from a file, if the fact is one the app could
+/// have made: whole operands, no dividing by zero, nothing that overflows
+fn checked_answer(fact: &Fact) -> Option<i32> {
+    let Fact { op, a, b } = *fact;
+    if a < 0 || b < 0 {
+        return None;
+    }
+    match op {
+        BaseOp::Add => a.checked_add(b),
+        BaseOp::Sub => a.checked_sub(b),
+        BaseOp::Mul => a.checked_mul(b),
+        BaseOp::Div => a.checked_div(b),
+    }
+}
+
+pub fn to_json(config: &QuizConfig, questions: &[Question], saved_ms: f64) -> String {
+    let file = QuizFile {
+        format: FORMAT.to_string(),
+        version: VERSION,
+        saved_ms,
+        config: config.clone(),
+        questions: questions.iter().map(blank_question).collect(),
+    };
+    serde_json::to_string_pretty(&file).unwrap_or_default()
+}
+
+/// Read a saved quiz, explaining what's wrong with files we can't use
+pub fn from_json(raw: &str) -> Result<(QuizConfig, Vec<Question>), String> {
+    let value: Value = serde_json::from_str(raw)
+        .map_err(|_| "That file isn't a saved quiz (it isn't valid JSON).".to_string())?;
+    if value.get("format").and_then(Value::as_str) != Some(FORMAT) {
+        return Err("That file isn't a Math Quest quiz.".to_string());
+    }
+    let version = value.get("version").and_then(Value::as_u64).unwrap_or(0);
+    if version > VERSION as u64 {
+        return Err(format!(
+            "This quiz was saved by a newer version of Math Quest (file version {version}, \
+             this app reads up to {VERSION}). Please update the app."
+        ));
+    }
+    let file: QuizFile =
+        serde_json::from_value(value).map_err(|err| format!("This quiz file is damaged: {err}"))?;
+    if file.questions.is_empty() {
+        return Err("This quiz file has no questions.".to_string());
+    }
+    for (i, q) in file.questions.iter().enumerate() {
+        let Some(fact) = &q.fact else { continue };
+        if checked_answer(fact) != Some(q.answer) {
+            return Err(format!(
+                "This quiz file is damaged: question {} ({} = {}) doesn't add up.",
+                i + 1,
+                fact_text(fact),
+                q.answer
+            ));
+        }
+    }
+    Ok((file.config, file.questions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::question_from_fact;
+
+    fn quiz(facts: &[(BaseOp, i32, i32)]) -> Vec<Question> {
+        facts
+            .iter()
+            .map(|&(op, a, b)| question_from_fact(Fact { op, a, b }, 1))
+            .collect()
+    }
+
+    fn saved(questions: &[Question]) -> String {
+        to_json(&QuizConfig::default(), questions, 0.0)
+    }
+
+    /// A saved file with one question's fact and answer replaced
+    fn tampered(op: &str, a: i64, b: i64, answer: i64) -> String {
+        let mut doc: Value = serde_json::from_str(&saved(&quiz(&[(BaseOp::Add, 2, 3)]))).unwrap();
+        let q = &mut doc["questions"][0];
+        q["fact"] = serde_json::json!({ "op": op, "a": a, "b": b });
+        q["answer"] = answer.into();
+        doc.to_string()
+    }
+
+    fn error(raw: &str) -> String {
+        match from_json(raw) {
+            Ok(_) => panic!("loaded a file that should be refused"),
+            Err(err) => err,
+        }
+    }
+
+    #[test]
+    fn saved_quiz_loads_back_blank() {
+        let mut questions = quiz(&[(BaseOp::Add, 7, 8), (BaseOp::Div, 56, 8)]);
+        questions[0].user_answer = "15".into();
+        questions[0].is_correct = Some(true);
+        let (config, loaded) = from_json(&saved(&questions)).unwrap();
+        assert!(config == QuizConfig::default());
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[0].user_answer, "");
+        assert_eq!(loaded[0].is_correct, None);
+        assert_eq!(loaded[1].answer, 7);
+    }
+
+    #[test]
+    fn newer_version_is_refused() {
+        let raw = saved(&quiz(&[(BaseOp::Add, 2, 3)])).replacen(
+            &format!("\"version\": {VERSION}"),
+            &format!("\"version\": {}", VERSION + 1),
+            1,
+        );
+        let err = error(&raw);
+        assert!(err.contains("newer version"), "{err}");
+    }
+
+    #[test]
+    fn empty_or_foreign_files_are_refused() {
+        assert!(error(&saved(&[])).contains("no questions"));
+        assert!(from_json("not json").is_err());
+        assert!(from_json(r#"{"format":"other"}"#).is_err());
+    }
+
+    #[test]
+    fn bad_facts_are_refused() {
+        assert!(from_json(&tampered("Add", 2, 3, 5)).is_ok());
+        for raw in [
+            tampered("Div", 5, 0, 0),
+            tampered("Mul", -3, 4, -12),
+            tampered("Add", 4, -1, 3),
+            tampered("Mul", 70_000, 70_000, 0),
+            tampered("Add", 2, 3, 6),
+        ] {
+            let err = error(&raw);
+            assert!(err.contains("question 1"), "{err}");
+        }
+    }
+}
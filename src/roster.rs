@@ -0,0 +1,414 @@
+//! Class roster import. A CSV with name, ID and group columns becomes
+//! student profiles, and each group can be given its own quiz setup.
+//! Everything stays in this browser.
+
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::profiles::{
+    add_profile, drop_empty_groups, group_config, group_names, GroupAssignment, ProfileList,
+};
+use crate::{difficulty_label, log, mix_summary, QuizConfig};
+
+/// One student line from the roster file
+#[derive(Clone, Debug, PartialEq)]
+pub struct RosterRow {
+    pub name: String,
+    pub student_id: String,
+    pub group: String,
+}
+
+/// Split one CSV line, honouring quotes and doubled quotes
+fn split_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' | ';' | '\t' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields.into_iter().map(|f| f.trim().to_string()).collect()
+}
+
+/// Columns by header name when there is a header, else name, ID, group
+fn columns(header: &[String]) -> Option<(usize, Option<usize>, Option<usize>)> {
+    let find = |names: &[&str]| {
+        header
+            .iter()
+            .position(|h| names.contains(&h.to_lowercase().as_str()))
+    };
+    let name = find(&["name", "student", "student name", "first name"])?;
+    let id = find(&["id", "student id", "student_id", "number"]);
+    let group = find(&["group", "class", "section", "team"]);
+    Some((name, id, group))
+}
+
+/// Read a roster CSV, explaining what's wrong with files we can't use
+pub fn parse_roster(raw: &str) -> Result<Vec<RosterRow>, String> {
+    let mut lines = raw
+        .trim_start_matches('\u{feff}')
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(split_line)
+        .peekable();
+    let Some(first) = lines.peek() else {
+        return Err("That roster file is empty.".to_string());
+    };
+    let (name, id, group) = match columns(first) {
+        Some(cols) => {
+            lines.next();
+            cols
+        }
+        None => (0, Some(1), Some(2)),
+    };
+
+    let cell = |fields: &[String], col: Option<usize>| {
+        col.and_then(|c| fields.get(c)).cloned().unwrap_or_default()
+    };
+    let rows: Vec<RosterRow> = lines
+        .map(|fields| RosterRow {
+            name: cell(&fields, Some(name)),
+            student_id: cell(&fields, id),
+            group: cell(&fields, group),
+        })
+        .filter(|row| !row.name.is_empty())
+        .collect();
+    if rows.is_empty() {
+        return Err(
+            "No students found. The roster needs a name column, then ID and group.".to_string(),
+        );
+    }
+    Ok(rows)
+}
+
+/// What an import did
+#[derive(Debug, Default, PartialEq)]
+pub struct ImportSummary {
+    pub added: usize,
+    pub updated: usize,
+    /// Names left out: shared by several students with no ID to tell
+    /// them apart
+    pub skipped: Vec<String>,
+}
+
+/// Add roster students to `list`, updating anyone already there (matched
+/// by ID, else by name). A name without an ID that fits more than one
+/// student is skipped rather than merged into the wrong profile.
+pub fn import_roster(
+    list: &mut ProfileList,
+    rows: &[RosterRow],
+    config: &QuizConfig,
+) -> ImportSummary {
+    let same_name = |a: &str, b: &str| a.eq_ignore_ascii_case(b);
+    let mut summary = ImportSummary::default();
+    for row in rows {
+        let existing = if row.student_id.is_empty() {
+            let in_roster = rows
+                .iter()
+                .filter(|r| same_name(&r.name, &row.name))
+                .count();
+            let on_device: Vec<usize> = (0..list.profiles.len())
+                .filter(|&i| same_name(&list.profiles[i].name, &row.name))
+                .collect();
+            if in_roster > 1 || on_device.len() > 1 {
+                if !summary.skipped.iter().any(|n| same_name(n, &row.name)) {
+                    summary.skipped.push(row.name.clone());
+                }
+                continue;
+            }
+            on_device.first().copied()
+        } else {
+            list.profiles.iter().position(|p| {
+                if p.student_id.is_empty() {
+                    same_name(&p.name, &row.name)
+                } else {
+                    p.student_id == row.student_id
+                }
+            })
+        };
+        let index = match existing {
+            Some(i) => {
+                summary.updated += 1;
+                i
+            }
+            None => {
+                add_profile(list, &row.name, config);
+                summary.added += 1;
+                list.profiles.len() - 1
+            }
+        };
+        let p = &mut list.profiles[index];
+        p.name = row.name.clone();
+        p.student_id = row.student_id.clone();
+        p.group = row.group.clone();
+    }
+    drop_empty_groups(list);
+    summary
+}
+
+/// "Easy · 5 +, 5 −"
+pub fn config_summary(config: &QuizConfig) -> String {
+    format!(
+        "{} · {}",
+        difficulty_label(&config.difficulty),
+        mix_summary(config)
+    )
+}
+
+#[derive(Properties, PartialEq)]
+pub struct RosterManagerProps {
+    pub list: ProfileList,
+    /// Setup on screen, assigned to a group on request
+    pub current_config: QuizConfig,
+    pub on_change: Callback<ProfileList>,
+}
+
+#[function_component(RosterManager)]
+pub fn roster_manager(props: &RosterManagerProps) -> Html {
+    let message = use_state(|| None::<String>);
+
+    let on_import = {
+        let list = props.list.clone();
+        let config = props.current_config.clone();
+        let on_change = props.on_change.clone();
+        let message = message.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let Some(file) = input.files().and_then(|files| files.get(0)) else {
+                return;
+            };
+            input.set_value("");
+            let list = list.clone();
+            let config = config.clone();
+            let on_change = on_change.clone();
+            let message = message.clone();
+            spawn_local(async move {
+                let text = match JsFuture::from(file.text()).await {
+                    Ok(text) => text.as_string().unwrap_or_default(),
+                    Err(err) => {
+                        log(&format!("Roster: could not read file: {:?}", err));
+                        message.set(Some("Could not read that file.".to_string()));
+                        return;
+                    }
+                };
+                match parse_roster(&text) {
+                    Ok(rows) => {
+                        let mut list = list.clone();
+                        let summary = import_roster(&mut list, &rows, &config);
+                        log(&format!(
+                            "Roster: {} added, {} updated, {} skipped",
+                            summary.added,
+                            summary.updated,
+                            summary.skipped.len()
+                        ));
+                        let mut text = format!(
+                            "Imported {} students: {} new, {} updated.",
+                            summary.added + summary.updated,
+                            summary.added,
+                            summary.updated
+                        );
+                        if !summary.skipped.is_empty() {
+                            text.push_str(&format!(
+                                " Skipped {} – more than one student has that name and there's no ID \
+                                 to tell them apart. Add student IDs to the roster and import again.",
+                                summary.skipped.join(", ")
+                            ));
+                        }
+                        message.set(Some(text));
+                        on_change.emit(list);
+                    }
+                    Err(msg) => message.set(Some(msg)),
+                }
+            });
+        })
+    };
+
+    // Give `group` the setup on screen, or take its assignment away
+    let assign = |group: &str, assign: bool| {
+        let list = props.list.clone();
+        let config = props.current_config.clone();
+        let on_change = props.on_change.clone();
+        let group = group.to_string();
+        Callback::from(move |_| {
+            let mut list = list.clone();
+            list.groups
+                .retain(|g| !g.group.eq_ignore_ascii_case(&group));
+            if assign {
+                list.groups.push(GroupAssignment {
+                    group: group.clone(),
+                    config: config.clone(),
+                });
+            }
+            on_change.emit(list);
+        })
+    };
+
+    let groups = group_names(&props.list);
+    html! {
+        <div>
+            <div class="btn-row">
+                <label class="btn-secondary file-button">
+                    {"Import roster (CSV)"}
+                    <input type="file" accept=".csv,text/csv" onchange={on_import} />
+                </label>
+            </div>
+            if let Some(msg) = (*message).clone() {
+                <div class="tiny-note">{msg}</div>
+            }
+            <div class="tiny-note">
+                {"Columns: name, ID, group (a header row is optional). "}
+                {"Students already here are matched by ID or name and keep their progress."}
+            </div>
+            if !groups.is_empty() {
+                <table class="results-table">
+                    <tr>
+                        <th>{"Group"}</th>
+                        <th>{"Students"}</th>
+                        <th>{"Assigned quiz"}</th>
+                        <th></th>
+                    </tr>
+                    { for groups.iter().map(|group| {
+                        let count = props
+                            .list
+                            .profiles
+                            .iter()
+                            .filter(|p| p.group.eq_ignore_ascii_case(group))
+                            .count();
+                        let assigned = group_config(&props.list, group);
+                        html! {
+                            <tr>
+                                <td>{group.clone()}</td>
+                                <td>{count}</td>
+                                <td>
+                                    {assigned.map(config_summary).unwrap_or_else(|| "Each student's default".to_string())}
+                                </td>
+                                <td>
+                                    <button class="btn-secondary" onclick={assign(group, true)}>
+                                        {"Assign current setup"}
+                                    </button>
+                                    if assigned.is_some() {
+                                        <button class="btn-secondary" onclick={assign(group, false)}>
+                                            {"Clear"}
+                                        </button>
+                                    }
+                                </td>
+                            </tr>
+                        }
+                    }) }
+                </table>
+                <div class="tiny-note">
+                    {"Set up a quiz above (say, Easy with + and − only), then assign it to a group. "}
+                    {"Students get their group's quiz when they pick their name."}
+                </div>
+            }
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(name: &str, student_id: &str, group: &str) -> RosterRow {
+        RosterRow {
+            name: name.to_string(),
+            student_id: student_id.to_string(),
+            group: group.to_string(),
+        }
+    }
+
+    #[test]
+    fn split_line_handles_quotes_and_separators() {
+        assert_eq!(split_line("Ana, 7, Blue"), ["Ana", "7", "Blue"]);
+        assert_eq!(split_line("Ana;7;Blue"), ["Ana", "7", "Blue"]);
+        assert_eq!(split_line("Ana\t7\tBlue"), ["Ana", "7", "Blue"]);
+        assert_eq!(
+            split_line(r#""Lee, Jo",12,"The ""A"" team""#),
+            ["Lee, Jo", "12", r#"The "A" team"#]
+        );
+        assert_eq!(split_line("Ana,,"), ["Ana", "", ""]);
+    }
+
+    #[test]
+    fn header_picks_columns_in_any_order() {
+        let rows = parse_roster("Group,Student Name,ID\nBlue,Ana,7\nRed,Ben,8\n").unwrap();
+        assert_eq!(rows, [row("Ana", "7", "Blue"), row("Ben", "8", "Red")]);
+    }
+
+    #[test]
+    fn no_header_means_name_id_group() {
+        let rows = parse_roster("Ana,7,Blue\r\n\r\nBen\r\n").unwrap();
+        assert_eq!(rows, [row("Ana", "7", "Blue"), row("Ben", "", "")]);
+    }
+
+    #[test]
+    fn quoted_commas_and_bom() {
+        let rows = parse_roster("\u{feff}name,group\n\"Lee, Jo\",\"Blue, 2nd\"\n").unwrap();
+        assert_eq!(rows, [row("Lee, Jo", "", "Blue, 2nd")]);
+    }
+
+    #[test]
+    fn unusable_files() {
+        assert!(parse_roster("").is_err());
+        assert!(parse_roster("\u{feff}\n  \n").is_err());
+        assert!(parse_roster("name,id\n,7\n").is_err());
+    }
+
+    #[test]
+    fn import_matches_by_id_then_name() {
+        let config = QuizConfig::default();
+        let mut list = ProfileList::default();
+        let first = import_roster(
+            &mut list,
+            &[row("Ana", "7", "Blue"), row("Ben", "", "Red")],
+            &config,
+        );
+        assert_eq!((first.added, first.updated), (2, 0));
+
+        // Ana renamed, Ben moved group
+        let again = import_roster(
+            &mut list,
+            &[row("Anna", "7", "Blue"), row("ben", "", "Blue")],
+            &config,
+        );
+        assert_eq!((again.added, again.updated), (0, 2));
+        assert_eq!(list.profiles[0].name, "Anna");
+        assert_eq!(list.profiles[1].group, "Blue");
+    }
+
+    #[test]
+    fn same_name_without_id_is_skipped() {
+        let config = QuizConfig::default();
+        let mut list = ProfileList::default();
+        let summary = import_roster(
+            &mut list,
+            &[
+                row("Sam", "", "Blue"),
+                row("sam", "", "Red"),
+                row("Ana", "", "Blue"),
+            ],
+            &config,
+        );
+        assert_eq!(summary.added, 1);
+        assert_eq!(summary.skipped, ["Sam"]);
+
+        // With IDs they are two students
+        let summary = import_roster(
+            &mut list,
+            &[row("Sam", "1", "Blue"), row("Sam", "2", "Red")],
+            &config,
+        );
+        assert_eq!((summary.added, summary.updated), (2, 0));
+        assert!(summary.skipped.is_empty());
+    }
+}
@@ -0,0 +1,410 @@
+//! Shareable quiz links. The URL fragment carries the setup, the seed the
+//! quiz was generated from and the word-problem text, so opening the link
+//! rebuilds the same quiz on any device without a server. The payload is
+//! kept small so the link still makes a QR code that scans from paper:
+//! only setup fields that differ from the link defaults, under short keys.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    generate_fallback_word_problem, generate_questions_with_ai_placeholders, mix_count_error,
+    with_seed, MixMode, Question, QuizConfig, AI_PLACEHOLDER, MAX_QUESTIONS, MIN_QUESTIONS,
+};
+
+/// Fragment prefix: `#quiz=<payload>`
+const FRAGMENT_KEY: &str = "#quiz=";
+
+/// Longest countdown a link may ask for, in seconds
+const MAX_TIME_LIMIT_SECS: u32 = 60 * 60;
+
+/// Bump whenever the generator would build a different quiz from the same
+/// seed, or `link_defaults` changes, so old links are refused instead of
+/// silently changing.
+pub const VERSION: u32 = 3;
+
+#[derive(Clone, PartialEq)]
+pub struct SharedWord {
+    pub index: usize,
+    pub prompt: String,
+    pub answer: i32,
+}
+
+#[derive(Clone, PartialEq)]
+pub struct ShareDoc {
+    pub v: u32,
+    pub config: QuizConfig,
+    pub seed: u64,
+    pub words: Vec<SharedWord>,
+}
+
+/// What actually goes in the link
+#[derive(Serialize, Deserialize)]
+struct Wire {
+    v: u32,
+    /// Setup fields that differ from `link_defaults`, by short key
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    c: Map<String, Value>,
+    s: u64,
+    /// Word problems as [index, prompt, answer]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    w: Vec<(usize, String, i32)>,
+}
+
+/// `QuizConfig` field names and their keys in the link. Never reuse a
+/// key for a different field.
+const SHORT_KEYS: [(&str, &str); 22] = [
+    ("num_questions", "n"),
+    ("difficulty", "d"),
+    ("include_add", "ia"),
+    ("include_sub", "is"),
+    ("include_mul", "im"),
+    ("include_div", "id"),
+    ("include_words", "iw"),
+    ("unique_facts", "u"),
+    ("commuted_equal", "ce"),
+    ("exclude_trivial", "et"),
+    ("one_at_a_time", "o"),
+    ("adaptive", "a"),
+    ("review_mode", "r"),
+    ("hint_penalty_pct", "h"),
+    ("time_limit_secs", "t"),
+    ("mix_mode", "mm"),
+    ("mix_add", "ma"),
+    ("mix_sub", "ms"),
+    ("mix_mul", "mu"),
+    ("mix_div", "md"),
+    ("mix_words", "mw"),
+    ("group_by_type", "g"),
+];
+
+/// The setup a link starts from, frozen for this `VERSION`. Kept apart from
+/// `QuizConfig::default()` so changing the app's defaults can't change
+/// what an existing link means.
+fn link_defaults() -> Value {
+    json!({
+        "num_questions": 10,
+        "difficulty": "Easy",
+        "include_add": true,
+        "include_sub": true,
+        "include_mul": false,
+        "include_div": false,
+        "include_words": true,
+        "unique_facts": true,
+        "commuted_equal": true,
+        "exclude_trivial": false,
+        "one_at_a_time": false,
+        "adaptive": false,
+        "review_mode": false,
+        "hint_penalty_pct": 25,
+        "time_limit_secs": 0,
+        "mix_mode": "Percent",
+        "mix_add": 40,
+        "mix_sub": 40,
+        "mix_mul": 40,
+        "mix_div": 40,
+        "mix_words": 20,
+        "group_by_type": false,
+    })
+}
+
+fn compact_config(cfg: &QuizConfig) -> Map<String, Value> {
+    let full = serde_json::to_value(cfg).unwrap_or_default();
+    let default = link_defaults();
+    SHORT_KEYS
+        .iter()
+        .filter_map(|&(long, short)| {
+            let value = full.get(long)?;
+            (Some(value) != default.get(long)).then(|| (short.to_string(), value.clone()))
+        })
+        .collect()
+}
+
+/// `None` for a key this version doesn't know or a value of the wrong type
+fn expand_config(compact: &Map<String, Value>) -> Option<QuizConfig> {
+    let mut full = serde_json::to_value(QuizConfig::default()).ok()?;
+    for (long, value) in link_defaults().as_object()? {
+        full[long] = value.clone();
+    }
+    for (key, value) in compact {
+        let &(long, _) = SHORT_KEYS.iter().find(|&&(_, short)| short == key)?;
+        full[long] = value.clone();
+    }
+    serde_json::from_value(full).ok()
+}
+
+const B64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// URL-safe base64 without padding
+fn encode_b64(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() * 4 / 3 + 3);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..=chunk.len() {
+            out.push(B64[(n >> (18 - 6 * i) & 63) as usize] as char);
+        }
+    }
+    out
+}
+
+fn decode_b64(text: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(text.len() * 3 / 4);
+    let mut bits = 0u32;
+    let mut count = 0;
+    for c in text.bytes() {
+        let v = B64.iter().position(|&b| b == c)? as u32;
+        bits = bits << 6 | v;
+        count += 6;
+        if count >= 8 {
+            count -= 8;
+            out.push((bits >> count) as u8);
+        }
+    }
+    Some(out)
+}
+
+/// Word problems in `questions` whose text has arrived from the Worker
+pub fn fixed_words(questions: &[Question]) -> Vec<SharedWord> {
+    questions
+        .iter()
+        .enumerate()
+        .filter(|(_, q)| q.kind.contains("Word Problem") && q.prompt != AI_PLACEHOLDER)
+        .map(|(index, q)| SharedWord {
+            index,
+            prompt: q.prompt.clone(),
+            answer: q.answer,
+        })
+        .collect()
+}
+
+/// Full link for the page this app is served from
+pub fn share_url(doc: &ShareDoc) -> String {
+    let base = web_sys::window()
+        .and_then(|w| {
+            let loc = w.location();
+            Some(format!("{}{}", loc.origin().ok()?, loc.pathname().ok()?))
+        })
+        .unwrap_or_default();
+    format!("{base}{FRAGMENT_KEY}{}", payload(doc))
+}
+
+/// The fragment text after `#quiz=`
+fn payload(doc: &ShareDoc) -> String {
+    let wire = Wire {
+        v: doc.v,
+        c: compact_config(&doc.config),
+        s: doc.seed,
+        w: doc
+            .words
+            .iter()
+            .map(|w| (w.index, w.prompt.clone(), w.answer))
+            .collect(),
+    };
+    let json = serde_json::to_string(&wire).unwrap_or_default();
+    encode_b64(json.as_bytes())
+}
+
+/// The quiz in the current URL: `None` without one, `Err` with a reason
+/// the student (or teacher) can act on.
+pub fn doc_from_location() -> Option<Result<ShareDoc, String>> {
+    let hash = web_sys::window()?.location().hash().ok()?;
+    let payload = hash.strip_prefix(FRAGMENT_KEY)?;
+    Some(parse_payload(payload))
+}
+
+fn parse_payload(payload: &str) -> Result<ShareDoc, String> {
+    let broken = || {
+        "This quiz link is broken or incomplete. Ask your teacher for the link again.".to_string()
+    };
+    let bytes = decode_b64(payload.trim()).ok_or_else(broken)?;
+    let value: serde_json::Value = serde_json::from_slice(&bytes).map_err(|_| broken())?;
+    let version = value
+        .get("v")
+        .and_then(serde_json::Value::as_u64)
+        .unwrap_or(0);
+    if version != VERSION as u64 {
+        return Err(if version > VERSION as u64 {
+            "This quiz link was made with a newer version of Math Quest. Reload the page to update."
+                .to_string()
+        } else {
+            "This quiz link is from an older version of Math Quest and can't rebuild the same \
+             quiz any more. Ask your teacher for a new link."
+                .to_string()
+        });
+    }
+    let wire: Wire = serde_json::from_value(value).map_err(|_| broken())?;
+    let config = expand_config(&wire.c).ok_or_else(broken)?;
+    if !config_in_range(&config) {
+        return Err(broken());
+    }
+    Ok(ShareDoc {
+        v: wire.v,
+        config,
+        seed: wire.s,
+        words: wire
+            .w
+            .into_iter()
+            .map(|(index, prompt, answer)| SharedWord {
+                index,
+                prompt,
+                answer,
+            })
+            .collect(),
+    })
+}
+
+/// Every count and percentage within what the setup screen allows
+fn config_in_range(cfg: &QuizConfig) -> bool {
+    let mix_max = match cfg.mix_mode {
+        MixMode::Percent => 100,
+        MixMode::Count => MAX_QUESTIONS as u32,
+    };
+    let mix = [
+        cfg.mix_add,
+        cfg.mix_sub,
+        cfg.mix_mul,
+        cfg.mix_div,
+        cfg.mix_words,
+    ];
+    (MIN_QUESTIONS..=MAX_QUESTIONS).contains(&cfg.num_questions)
+        && mix.iter().all(|&v| v <= mix_max)
+        && mix_count_error(cfg).is_none()
+        && cfg.hint_penalty_pct <= 100
+        && cfg.time_limit_secs <= MAX_TIME_LIMIT_SECS
+}
+
+/// Rebuild the quiz a link describes
+pub fn rebuild(doc: &ShareDoc) -> Result<Vec<Question>, String> {
+    let mut qs = with_seed(doc.seed, || {
+        generate_questions_with_ai_placeholders(&doc.config)
+    })?;
+    for (i, q) in qs.iter_mut().enumerate() {
+        if !q.kind.contains("Word Problem") {
+            continue;
+        }
+        let (prompt, answer, kind) = match doc.words.iter().find(|w| w.index == i) {
+            Some(w) => (w.prompt.clone(), w.answer, q.kind.clone()),
+            // Shared before the Worker answered: a local problem, same on every device
+            None => with_seed(doc.seed ^ i as u64, || {
+                generate_fallback_word_problem(&doc.config)
+            }),
+        };
+        q.prompt = prompt;
+        q.answer = answer;
+        q.kind = kind;
+    }
+    Ok(qs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Difficulty;
+
+    fn doc(config: QuizConfig) -> ShareDoc {
+        ShareDoc {
+            v: VERSION,
+            config,
+            seed: 12345,
+            words: vec![SharedWord {
+                index: 3,
+                prompt: "Mia has 4 apples and gets 3 more. How many now?".into(),
+                answer: 7,
+            }],
+        }
+    }
+
+    fn error(payload: &str) -> String {
+        match parse_payload(payload) {
+            Ok(_) => panic!("accepted a link that should be refused"),
+            Err(err) => err,
+        }
+    }
+
+    #[test]
+    fn b64_round_trips_every_length() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        for len in 0..10 {
+            let text = encode_b64(&bytes[250 - len..250]);
+            assert_eq!(text.len(), (len * 4).div_ceil(3));
+            assert_eq!(decode_b64(&text).unwrap(), &bytes[250 - len..250]);
+        }
+        let text = encode_b64(&bytes);
+        assert!(text.bytes().all(|c| B64.contains(&c)));
+        assert_eq!(decode_b64(&text).unwrap(), bytes);
+    }
+
+    #[test]
+    fn b64_matches_known_text() {
+        // One, two and three bytes: 1, 2 and 0 mod 3
+        assert_eq!(encode_b64(b"M"), "TQ");
+        assert_eq!(encode_b64(b"Ma"), "TWE");
+        assert_eq!(encode_b64(b"Man"), "TWFu");
+        assert_eq!(encode_b64(b"Many"), "TWFueQ");
+        assert_eq!(encode_b64(&[0xfb, 0xff]), "-_8");
+        assert_eq!(decode_b64("TWFueQ").unwrap(), b"Many");
+    }
+
+    #[test]
+    fn b64_refuses_other_characters() {
+        for text in ["TWE=", "TW Fu", "+/8", "TWFu!", "é"] {
+            assert!(decode_b64(text).is_none(), "{text}");
+        }
+    }
+
+    #[test]
+    fn payload_round_trips() {
+        let config = QuizConfig {
+            num_questions: 15,
+            difficulty: Difficulty::Moderate,
+            include_mul: true,
+            mix_mul: 10,
+            time_limit_secs: 300,
+            ..QuizConfig::default()
+        };
+        let original = doc(config);
+        let parsed = parse_payload(&payload(&original)).unwrap();
+        assert!(parsed == original);
+    }
+
+    #[test]
+    fn link_defaults_stay_out_of_the_payload() {
+        let defaults: QuizConfig = serde_json::from_value(link_defaults()).unwrap();
+        assert!(compact_config(&defaults).is_empty());
+        assert!(expand_config(&Map::new()).unwrap() == defaults);
+
+        let changed = QuizConfig {
+            mix_words: 30,
+            ..defaults
+        };
+        let compact = compact_config(&changed);
+        assert_eq!(compact.len(), 1);
+        assert_eq!(compact["mw"], 30);
+    }
+
+    #[test]
+    fn link_defaults_cover_every_shared_field() {
+        let defaults = link_defaults();
+        for (long, _) in SHORT_KEYS {
+            assert!(defaults.get(long).is_some(), "{long}");
+        }
+    }
+
+    #[test]
+    fn bad_payloads_are_refused() {
+        assert!(error("not*base64").contains("broken"));
+        assert!(error(&encode_b64(b"{not json")).contains("broken"));
+
+        let wire =
+            |v: u32, c: Value| encode_b64(json!({ "v": v, "c": c, "s": 1 }).to_string().as_bytes());
+        assert!(error(&wire(VERSION + 1, json!({}))).contains("newer version"));
+        assert!(error(&wire(VERSION - 1, json!({}))).contains("older version"));
+        assert!(error(&wire(VERSION, json!({ "zz": 1 }))).contains("broken"));
+        assert!(error(&wire(VERSION, json!({ "n": "ten" }))).contains("broken"));
+        assert!(error(&wire(VERSION, json!({ "n": 500 }))).contains("broken"));
+        assert!(parse_payload(&wire(VERSION, json!({ "n": 12 }))).is_ok());
+    }
+}
@@ -0,0 +1,377 @@
+//! Worked solutions shown after grading and in the teacher answer key.
+
+use yew::prelude::*;
+
+use crate::{fact_answer, BaseOp, Fact, Question};
+
+#[derive(Clone, PartialEq)]
+pub struct WorkedSolution {
+    /// Column layout (monospace), for multi-digit + and −
+    pub layout: Option<String>,
+    pub steps: Vec<String>,
+}
+
+const PLACE_NAMES: [&str; 4] = ["ones", "tens", "hundreds", "thousands"];
+
+fn place_name(i: usize) -> &'static str {
+    PLACE_NAMES.get(i).copied().unwrap_or("next place")
+}
+
+fn digit(n: i32, i: usize) -> i32 {
+    n / 10_i32.pow(i as u32) % 10
+}
+
+fn width(n: i32) -> usize {
+    n.to_string().len()
+}
+
+/// Stacked layout with a marks row for carries/borrows:
+/// ```text
+///    1
+///    47
+///  + 38
+///  ----
+///    85
+/// ```
+fn column_layout(a: i32, b: i32, sym: &str, result: i32, marked: &[usize]) -> String {
+    let w = width(a).max(width(b)).max(width(result));
+    let marks: String = (0..w)
+        .rev()
+        .map(|i| if marked.contains(&i) { '1' } else { ' ' })
+        .collect();
+    format!(
+        "  {marks}\n  {a:>w$}\n{sym} {b:>w$}\n  {}\n  {result:>w$}",
+        "-".repeat(w)
+    )
+}
+
+fn add_solution(a: i32, b: i32) -> WorkedSolution {
+    let sum = a + b;
+    if a < 10 && b < 10 {
+        let (big, small) = if a >= b { (a, b) } else { (b, a) };
+        let steps = if big + small > 10 {
+            let to_ten = 10 - big;
+            vec![
+                format!("Make a ten: {big} + {to_ten} = 10."),
+                format!("{small} − {to_ten} = {} left over.", small - to_ten),
+                format!("10 + {} = {sum}.", small - to_ten),
+            ]
+        } else {
+            vec![format!("Count on from {big}: {small} more makes {sum}.")]
+        };
+        return WorkedSolution {
+            layout: None,
+            steps,
+        };
+    }
+
+    let cols = width(a).max(width(b));
+    let mut carry = 0;
+    let mut marked = Vec::new();
+    let mut steps = Vec::new();
+    for i in 0..cols {
+        let (da, db) = (digit(a, i), digit(b, i));
+        let total = da + db + carry;
+        let carry_text = if carry > 0 { " + 1 (carried)" } else { "" };
+        if total >= 10 {
+            steps.push(format!(
+                "{}: {da} + {db}{carry_text} = {total}. Write {}, carry 1 to the {}.",
+                capitalize(place_name(i)),
+                total % 10,
+                place_name(i + 1)
+            ));
+            marked.push(i + 1);
+            carry = 1;
+        } else {
+            steps.push(format!(
+                "{}: {da} + {db}{carry_text} = {total}.",
+                capitalize(place_name(i))
+            ));
+            carry = 0;
+        }
+    }
+    if carry > 0 {
+        steps.push(format!(
+            "Bring down the carried 1 into the {}.",
+            place_name(cols)
+        ));
+    }
+    steps.push(format!("{a} + {b} = {sum}"));
+    WorkedSolution {
+        layout: Some(column_layout(a, b, "+", sum, &marked)),
+        steps,
+    }
+}
+
+fn sub_solution(a: i32, b: i32) -> WorkedSolution {
+    let diff = a - b;
+    if a < 20 && b < 10 {
+        return WorkedSolution {
+            layout: None,
+            steps: vec![
+                format!("Think addition: {b} + ? = {a}."),
+                format!("Count up from {b} to {a}: that's {diff} steps."),
+                format!("{a} − {b} = {diff}"),
+            ],
+        };
+    }
+
+    let cols = width(a);
+    // Digits of `a` as they stand after each borrow
+    let mut work: Vec<i32> = (0..cols).map(|i| digit(a, i)).collect();
+    let mut lent = vec![false; cols];
+    let mut marked = Vec::new();
+    let mut steps = Vec::new();
+    for i in 0..cols {
+        let da = work[i];
+        let db = digit(b, i);
+        let borrowed_text = if lent[i] { " (after lending 1)" } else { "" };
+        // Nearest place on the left with something to lend
+        let lender = (i + 1..cols).find(|&j| work[j] > 0);
+        match lender {
+            Some(j) if da < db => {
+                work[j] -= 1;
+                lent[j] = true;
+                for k in i + 1..j {
+                    work[k] = 9;
+                    lent[k] = true;
+                }
+                let through = if j > i + 1 {
+                    let zeros: Vec<&str> = (i + 1..j).map(place_name).collect();
+                    format!(
+                        ". The {} {} 0, so borrow from the {} and each 0 on the way becomes 9",
+                        zeros.join(" and "),
+                        if zeros.len() == 1 {
+                            "digit is"
+                        } else {
+                            "digits are"
+                        },
+                        place_name(j)
+                    )
+                } else {
+                    format!(", so borrow 1 from the {}", place_name(j))
+                };
+                steps.push(format!(
+                    "{}: {da}{borrowed_text} is less than {db}{through}: {} − {db} = {}.",
+                    capitalize(place_name(i)),
+                    da + 10,
+                    da + 10 - db
+                ));
+                marked.push(i);
+            }
+            _ => steps.push(format!(
+                "{}: {da}{borrowed_text} − {db} = {}.",
+                capitalize(place_name(i)),
+                da - db
+            )),
+        }
+    }
+    steps.push(format!("Check: {diff} + {b} = {a} ✔"));
+    WorkedSolution {
+        layout: Some(column_layout(a, b, "−", diff, &marked)),
+        steps,
+    }
+}
+
+fn mul_solution(a: i32, b: i32) -> WorkedSolution {
+    let product = a * b;
+    let steps = if b == 0 || a == 0 {
+        vec![format!("Anything times 0 is 0, so {a} × {b} = 0.")]
+    } else if b <= 10 && a <= 12 {
+        let repeated = vec![a.to_string(); b as usize].join(" + ");
+        let skips: Vec<String> = (1..=b).map(|i| (a * i).to_string()).collect();
+        vec![
+            format!("{a} × {b} means {b} groups of {a}: {repeated}."),
+            format!("Skip count by {a}: {}.", skips.join(", ")),
+            format!("{a} × {b} = {product}"),
+        ]
+    } else {
+        // Split the bigger factor into tens and ones
+        let (small, big) = (a.min(b), a.max(b));
+        let (tens, ones) = (big / 10 * 10, big % 10);
+        if ones == 0 {
+            vec![
+                format!("{big} is {} tens.", big / 10),
+                format!(
+                    "{small} × {} = {}, so {small} × {big} is {} tens.",
+                    big / 10,
+                    product / 10,
+                    product / 10
+                ),
+                format!("{a} × {b} = {product}"),
+            ]
+        } else {
+            vec![
+                format!("Break {big} into {tens} + {ones}."),
+                format!(
+                    "{small} × {tens} = {} and {small} × {ones} = {}.",
+                    small * tens,
+                    small * ones
+                ),
+                format!("{} + {} = {product}", small * tens, small * ones),
+            ]
+        }
+    };
+    WorkedSolution {
+        layout: None,
+        steps,
+    }
+}
+
+fn div_solution(a: i32, b: i32) -> WorkedSolution {
+    let quotient = a / b;
+    let mut steps = Vec::new();
+    if quotient <= 12 {
+        let skips: Vec<String> = (1..=quotient).map(|i| (b * i).to_string()).collect();
+        steps.push(format!(
+            "Count by {b}s up to {a}: {} — that's {quotient} jumps.",
+            if skips.is_empty() {
+                "0".to_string()
+            } else {
+                skips.join(", ")
+            }
+        ));
+    }
+    steps.push(format!(
+        "Check with multiplication: {b} × {quotient} = {a}."
+    ));
+    steps.push(format!("{a} ÷ {b} = {quotient}"));
+    WorkedSolution {
+        layout: None,
+        steps,
+    }
+}
+
+/// The number sentence behind a word problem, found by trying each
+/// operation on the first two numbers in the story
+fn word_solution(q: &Question) -> WorkedSolution {
+    let nums: Vec<i32> = q
+        .prompt
+        .split(|c: char| !c.is_ascii_digit())
+        .filter_map(|w| w.parse().ok())
+        .collect();
+    let mut steps = Vec::new();
+    if let [x, y, ..] = nums[..] {
+        steps.push(format!("The key numbers are {x} and {y}."));
+        let candidates = [
+            (BaseOp::Add, x, y),
+            (BaseOp::Sub, x.max(y), x.min(y)),
+            (BaseOp::Mul, x, y),
+            (BaseOp::Div, x.max(y), x.min(y)),
+        ];
+        let found = candidates.iter().find(|&&(op, a, b)| {
+            (op != BaseOp::Div || (b != 0 && a % b == 0))
+                && fact_answer(&Fact { op, a, b }) == q.answer
+        });
+        if let Some(&(op, a, b)) = found {
+            steps.push(format!(
+                "Number sentence: {} = {}",
+                crate::fact_text(&Fact { op, a, b }),
+                q.answer
+            ));
+        }
+    }
+    if steps.len() < 2 {
+        steps.push(format!("The answer is {}.", q.answer));
+    }
+    WorkedSolution {
+        layout: None,
+        steps,
+    }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) => c.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+pub fn worked_solution(q: &Question) -> WorkedSolution {
+    match q.fact {
+        Some(Fact {
+            op: BaseOp::Add,
+            a,
+            b,
+        }) => add_solution(a, b),
+        Some(Fact {
+            op: BaseOp::Sub,
+            a,
+            b,
+        }) => sub_solution(a, b),
+        Some(Fact {
+            op: BaseOp::Mul,
+            a,
+            b,
+        }) => mul_solution(a, b),
+        Some(Fact {
+            op: BaseOp::Div,
+            a,
+            b,
+        }) => div_solution(a, b),
+        None => word_solution(q),
+    }
+}
+
+pub fn view_solution(sol: &WorkedSolution) -> Html {
+    html! {
+        <div class="worked-solution">
+            if let Some(layout) = &sol.layout {
+                <pre class="column-layout">{layout.clone()}</pre>
+            }
+            <ol>
+                { for sol.steps.iter().map(|s| html! { <li>{s.clone()}</li> }) }
+            </ol>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn borrow_runs_through_every_zero() {
+        let sol = sub_solution(1000, 1);
+        assert_eq!(
+            sol.steps[0],
+            "Ones: 0 is less than 1. The tens and hundreds digits are 0, \
+             so borrow from the thousands and each 0 on the way becomes 9: 10 − 1 = 9."
+        );
+        assert_eq!(sol.steps[1], "Tens: 9 (after lending 1) − 0 = 9.");
+        assert_eq!(sol.steps[2], "Hundreds: 9 (after lending 1) − 0 = 9.");
+        assert_eq!(sol.steps.last().unwrap(), "Check: 999 + 1 = 1000 ✔");
+        assert!(sol.layout.unwrap().ends_with(" 999"));
+    }
+
+    #[test]
+    fn borrow_through_one_zero() {
+        let sol = sub_solution(503, 278);
+        assert_eq!(
+            sol.steps,
+            [
+                "Ones: 3 is less than 8. The tens digit is 0, so borrow from the hundreds \
+                 and each 0 on the way becomes 9: 13 − 8 = 5.",
+                "Tens: 9 (after lending 1) − 7 = 2.",
+                "Hundreds: 4 (after lending 1) − 2 = 2.",
+                "Check: 225 + 278 = 503 ✔",
+            ]
+        );
+    }
+
+    #[test]
+    fn final_carry_is_brought_down() {
+        let sol = add_solution(57, 68);
+        assert_eq!(
+            sol.steps,
+            [
+                "Ones: 7 + 8 = 15. Write 5, carry 1 to the tens.",
+                "Tens: 5 + 6 + 1 (carried) = 12. Write 2, carry 1 to the hundreds.",
+                "Bring down the carried 1 into the hundreds.",
+                "57 + 68 = 125",
+            ]
+        );
+        assert_eq!(sol.layout.unwrap(), "  11 \n   57\n+  68\n  ---\n  125");
+    }
+}
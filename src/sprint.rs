@@ -0,0 +1,448 @@
+//! Mad-minute fluency sprint: basic facts back to back against the clock,
+//! scored like a curriculum-based measurement probe (digits correct per
+//! minute as well as problems correct).
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use wasm_bindgen_futures::spawn_local;
+use web_sys::HtmlInputElement;
+use yew::platform::time::sleep;
+use yew::prelude::*;
+
+use crate::timing::{self, BrowserClock, Clock};
+use crate::{difficulty_label, generate_basic_question, op_symbol, with_seed, BaseOp, QuizConfig};
+
+/// Number of fixed alternate forms offered for progress monitoring
+pub const FORM_COUNT: u32 = 10;
+
+#[derive(Clone, PartialEq)]
+pub struct SprintItem {
+    pub prompt: String,
+    pub answer: i32,
+    pub given: String,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct SprintScore {
+    pub attempted: usize,
+    pub correct: usize,
+    pub digits_correct: u32,
+    /// Digits correct per minute
+    pub dcpm: f64,
+}
+
+/// Basic operations the sprint cycles through (addition if none are ticked)
+pub fn sprint_ops(cfg: &QuizConfig) -> Vec<BaseOp> {
+    let ops: Vec<BaseOp> = [
+        (cfg.include_add, BaseOp::Add),
+        (cfg.include_sub, BaseOp::Sub),
+        (cfg.include_mul, BaseOp::Mul),
+        (cfg.include_div, BaseOp::Div),
+    ]
+    .into_iter()
+    .filter(|(on, _)| *on)
+    .map(|(_, op)| op)
+    .collect();
+    if ops.is_empty() {
+        vec![BaseOp::Add]
+    } else {
+        ops
+    }
+}
+
+/// Seed for alternate form `form`; same form + same settings = same probe
+fn form_seed(cfg: &QuizConfig, ops: &[BaseOp], form: u32) -> u64 {
+    let tier = match cfg.difficulty {
+        crate::Difficulty::Easy => 1u64,
+        crate::Difficulty::Moderate => 2,
+        crate::Difficulty::Advanced => 3,
+    };
+    let op_bits = ops
+        .iter()
+        .fold(0u64, |bits, &op| bits | 1 << crate::op_index(op));
+    (form as u64) << 32 | tier << 8 | op_bits
+}
+
+/// Problem number `n` of a sprint. Operations rotate in a fixed order so
+/// every form has the same mix; with a form number the numbers are fixed too.
+pub fn sprint_problem(
+    cfg: &QuizConfig,
+    ops: &[BaseOp],
+    form: Option<u32>,
+    n: usize,
+) -> (String, i32) {
+    let op = ops[n % ops.len()];
+    let (prompt, answer, _) = match form {
+        Some(form) => {
+            let seed = form_seed(cfg, ops, form) ^ (n as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+            with_seed(seed, || generate_basic_question(cfg, op))
+        }
+        None => generate_basic_question(cfg, op),
+    };
+    (prompt, answer)
+}
+
+/// CBM scoring: one point for each digit of the answer written in the
+/// right place, counted from the ones column. Non-numeric input scores 0.
+pub fn digits_correct(answer: i32, given: &str) -> u32 {
+    let given = given.trim();
+    if given.is_empty() || !given.chars().all(|c| c.is_ascii_digit()) {
+        return 0;
+    }
+    let given = given.trim_start_matches('0');
+    let given = if given.is_empty() { "0" } else { given };
+    answer
+        .to_string()
+        .chars()
+        .rev()
+        .zip(given.chars().rev())
+        .filter(|(want, got)| want == got)
+        .count() as u32
+}
+
+pub fn score(items: &[SprintItem], minutes: u32) -> SprintScore {
+    let attempted: Vec<&SprintItem> = items
+        .iter()
+        .filter(|i| !i.given.trim().is_empty())
+        .collect();
+    let correct = attempted
+        .iter()
+        .filter(|i| i.given.trim().parse::<i32>() == Ok(i.answer))
+        .count();
+    let digits_correct: u32 = attempted
+        .iter()
+        .map(|i| digits_correct(i.answer, &i.given))
+        .sum();
+    SprintScore {
+        attempted: attempted.len(),
+        correct,
+        digits_correct,
+        dcpm: digits_correct as f64 / minutes.max(1) as f64,
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct SprintViewProps {
+    /// Difficulty and operations come from the quiz setup
+    pub config: QuizConfig,
+}
+
+#[function_component(SprintView)]
+pub fn sprint_view(props: &SprintViewProps) -> Html {
+    let minutes = use_state(|| 1u32);
+    // 0 = fresh numbers every time, otherwise a fixed alternate form
+    let form = use_state(|| 0u32);
+    let items = use_state(Vec::<SprintItem>::new);
+    let current = use_state(|| None::<(usize, String, i32)>);
+    let answer = use_state(String::new);
+    let deadline = use_state(|| None::<f64>);
+    let now_tick = use_state(|| 0.0f64);
+    let finished = use_state(|| false);
+    let input_ref = use_node_ref();
+
+    let ops = sprint_ops(&props.config);
+    let form_no = (*form > 0).then_some(*form);
+
+    let on_minutes = {
+        let minutes = minutes.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlInputElement = e.target_unchecked_into();
+            minutes.set(select.value().parse::<u32>().unwrap_or(1).clamp(1, 5));
+        })
+    };
+
+    let on_form = {
+        let form = form.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlInputElement = e.target_unchecked_into();
+            form.set(select.value().parse::<u32>().unwrap_or(0).min(FORM_COUNT));
+        })
+    };
+
+    let on_start = {
+        let cfg = props.config.clone();
+        let ops = ops.clone();
+        let minutes = minutes.clone();
+        let items = items.clone();
+        let current = current.clone();
+        let answer = answer.clone();
+        let deadline = deadline.clone();
+        let finished = finished.clone();
+        Callback::from(move |_| {
+            let (prompt, value) = sprint_problem(&cfg, &ops, form_no, 0);
+            items.set(Vec::new());
+            current.set(Some((0, prompt, value)));
+            answer.set(String::new());
+            finished.set(false);
+            deadline.set(timing::deadline(*minutes * 60, &BrowserClock));
+        })
+    };
+
+    let on_answer = {
+        let answer = answer.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            answer.set(input.value());
+        })
+    };
+
+    // Enter moves straight on, right or wrong, like a paper probe
+    let on_next = {
+        let cfg = props.config.clone();
+        let ops = ops.clone();
+        let items = items.clone();
+        let current = current.clone();
+        let answer = answer.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let Some((n, prompt, value)) = (*current).clone() else {
+                return;
+            };
+            let mut done = (*items).clone();
+            done.push(SprintItem {
+                prompt,
+                answer: value,
+                given: (*answer).clone(),
+            });
+            items.set(done);
+            let (prompt, value) = sprint_problem(&cfg, &ops, form_no, n + 1);
+            current.set(Some((n + 1, prompt, value)));
+            answer.set(String::new());
+        })
+    };
+
+    {
+        let now_tick = now_tick.clone();
+        use_effect_with(*deadline, move |&deadline| {
+            let alive = Rc::new(Cell::new(true));
+            if deadline.is_some() {
+                let alive = alive.clone();
+                spawn_local(async move {
+                    while alive.get() {
+                        now_tick.set(BrowserClock.now_ms());
+                        sleep(Duration::from_millis(250)).await;
+                    }
+                });
+            }
+            move || alive.set(false)
+        });
+    }
+
+    let secs_left = deadline.map(|d| timing::seconds_left(d, &BrowserClock));
+    let time_up = secs_left == Some(0);
+
+    // Time's up: a half-typed answer still counts, as it would on paper
+    {
+        let items = items.clone();
+        let current = current.clone();
+        let answer = answer.clone();
+        let deadline = deadline.clone();
+        let finished = finished.clone();
+        use_effect_with(time_up, move |&up| {
+            if up {
+                if let Some((_, prompt, value)) = (*current).clone() {
+                    if !answer.trim().is_empty() {
+                        let mut done = (*items).clone();
+                        done.push(SprintItem {
+                            prompt,
+                            answer: value,
+                            given: (*answer).clone(),
+                        });
+                        items.set(done);
+                    }
+                }
+                current.set(None);
+                deadline.set(None);
+                finished.set(true);
+            }
+            || ()
+        });
+    }
+
+    let running = current.is_some();
+
+    {
+        let input_ref = input_ref.clone();
+        use_effect_with(running, move |&running| {
+            if running {
+                if let Some(input) = input_ref.cast::<HtmlInputElement>() {
+                    let _ = input.focus();
+                }
+            }
+            || ()
+        });
+    }
+    let result = score(&items, *minutes);
+    let op_list = ops
+        .iter()
+        .map(|&op| op_symbol(op))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let probe_label = format!(
+        "{} · {} · {} min · {}",
+        difficulty_label(&props.config.difficulty),
+        op_list,
+        *minutes,
+        match form_no {
+            Some(f) => format!("Form {f}"),
+            None => "fresh numbers".to_string(),
+        }
+    );
+
+    html! {
+        <div>
+            <div class="config-grid">
+                <div>
+                    <div class="field-label">
+                        <span>{"Sprint length"}</span>
+                    </div>
+                    <select
+                        class="field-input"
+                        disabled={running}
+                        onchange={on_minutes}
+                    >
+                        { for (1..=5u32).map(|m| html! {
+                            <option value={m.to_string()} selected={m == *minutes}>
+                                {if m == 1 { "1 minute".to_string() } else { format!("{m} minutes") }}
+                            </option>
+                        }) }
+                    </select>
+                </div>
+                <div>
+                    <div class="field-label">
+                        <span>{"Probe form"}</span>
+                        <span class="field-hint">{"same form = same problems"}</span>
+                    </div>
+                    <select class="field-input" disabled={running} onchange={on_form}>
+                        <option value="0" selected={*form == 0}>{"Fresh numbers"}</option>
+                        { for (1..=FORM_COUNT).map(|f| html! {
+                            <option value={f.to_string()} selected={f == *form}>
+                                {format!("Form {f}")}
+                            </option>
+                        }) }
+                    </select>
+                </div>
+            </div>
+            <div class="tiny-note">
+                {format!(
+                    "Uses the difficulty and +, −, ×, ÷ choices from the quiz setup: {}.",
+                    probe_label
+                )}
+            </div>
+
+            if !running {
+                <div class="btn-row">
+                    <button class="btn-primary" onclick={on_start}>
+                        {if *finished { "Sprint again" } else { "Start sprint" }}
+                    </button>
+                </div>
+            }
+
+            <div class={if running { "sprint-stage" } else { "sprint-stage hidden" }}>
+                if let Some(secs) = secs_left {
+                    <div class={classes!("countdown", (secs <= 10).then_some("low"))}>
+                        {format!("⏱ {} left", timing::format_clock(secs))}
+                    </div>
+                }
+                <form class="answer-row" onsubmit={on_next}>
+                    <div class="sprint-prompt">
+                        {current.as_ref().map(|(_, prompt, _)| prompt.clone()).unwrap_or_default()}
+                    </div>
+                    <input
+                        ref={input_ref}
+                        class="answer-input"
+                        type="text"
+                        inputmode="numeric"
+                        value={(*answer).clone()}
+                        oninput={on_answer}
+                    />
+                    <button class="btn-primary" type="submit">{"Next ➜"}</button>
+                </form>
+                <div class="tiny-note">{format!("{} done", items.len())}</div>
+            </div>
+
+            if *finished {
+                <div class="score-banner">
+                    <div>
+                        <span class="score-main">
+                            {format!("{:.0} digits correct per minute", result.dcpm)}
+                        </span>
+                    </div>
+                    <div class="tiny-note">
+                        {format!(
+                            "Problems correct: {} of {} attempted · Digits correct: {}",
+                            result.correct, result.attempted, result.digits_correct
+                        )}
+                    </div>
+                    <div class="tiny-note">{probe_label.clone()}</div>
+                </div>
+                <table class="results-table">
+                    <tr>
+                        <th>{"#"}</th>
+                        <th>{"Problem"}</th>
+                        <th>{"Answer"}</th>
+                        <th>{"Wrote"}</th>
+                        <th>{"Digits"}</th>
+                    </tr>
+                    { for items.iter().enumerate().map(|(i, item)| html! {
+                        <tr>
+                            <td>{i + 1}</td>
+                            <td>{item.prompt.clone()}</td>
+                            <td>{item.answer}</td>
+                            <td>{item.given.clone()}</td>
+                            <td>{digits_correct(item.answer, &item.given)}</td>
+                        </tr>
+                    }) }
+                </table>
+            }
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(answer: i32, given: &str) -> SprintItem {
+        SprintItem {
+            prompt: String::new(),
+            answer,
+            given: given.to_string(),
+        }
+    }
+
+    #[test]
+    fn digits_count_by_place() {
+        assert_eq!(digits_correct(125, "125"), 3);
+        assert_eq!(digits_correct(125, "135"), 2);
+        assert_eq!(digits_correct(125, "25"), 2);
+        assert_eq!(digits_correct(125, "521"), 1);
+        assert_eq!(digits_correct(7, " 7 "), 1);
+        assert_eq!(digits_correct(7, "007"), 1);
+        assert_eq!(digits_correct(0, "000"), 1);
+        assert_eq!(digits_correct(12, ""), 0);
+        assert_eq!(digits_correct(12, "1x"), 0);
+        assert_eq!(digits_correct(12, "-12"), 0);
+    }
+
+    #[test]
+    fn score_skips_blank_answers() {
+        let items = [
+            item(12, "12"),
+            item(45, "44"),
+            item(8, ""),
+            item(30, "  "),
+            item(100, "100"),
+        ];
+        let s = score(&items, 2);
+        assert_eq!(s.attempted, 3);
+        assert_eq!(s.correct, 2);
+        assert_eq!(s.digits_correct, 6);
+        assert_eq!(s.dcpm, 3.0);
+
+        // A zero-minute probe is scored per minute, not divided by zero
+        assert_eq!(score(&items, 0).dcpm, 6.0);
+    }
+}
@@ -0,0 +1,64 @@
+//! Small JSON-over-localStorage helpers.
+//! Everything the app remembers between visits goes through here.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use web_sys::Storage;
+
+use crate::log;
+
+/// Prefix for every key we write, so we don't collide with other apps
+const KEY_PREFIX: &str = "math-quest";
+
+fn local_storage() -> Option<Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+/// Storage key for a piece of per-student data
+pub fn student_key(kind: &str, student: &str) -> String {
+    let student = student.trim();
+    let student = if student.is_empty() { "guest" } else { student };
+    format!("{KEY_PREFIX}:{kind}:{}", student.to_lowercase())
+}
+
+/// Storage key for app-wide data
+pub fn app_key(kind: &str) -> String {
+    format!("{KEY_PREFIX}:{kind}")
+}
+
+/// Read and decode a value; missing or unreadable data gives `None`
+pub fn load_json<T: DeserializeOwned>(key: &str) -> Option<T> {
+    let raw = local_storage()?.get_item(key).ok()??;
+    match serde_json::from_str(&raw) {
+        Ok(value) => Some(value),
+        Err(err) => {
+            log(&format!("storage: could not decode {key}: {err}"));
+            None
+        }
+    }
+}
+
+/// Forget a stored value
+pub fn remove_item(key: &str) {
+    if let Some(storage) = local_storage() {
+        if storage.remove_item(key).is_err() {
+            log(&format!("storage: could not remove {key}"));
+        }
+    }
+}
+
+/// Encode and write a value; failures (quota, private mode) are logged
+pub fn save_json<T: Serialize>(key: &str, value: &T) {
+    let Some(storage) = local_storage() else {
+        log("storage: localStorage unavailable");
+        return;
+    };
+    match serde_json::to_string(value) {
+        Ok(raw) => {
+            if storage.set_item(key, &raw).is_err() {
+                log(&format!("storage: could not write {key}"));
+            }
+        }
+        Err(err) => log(&format!("storage: could not encode {key}: {err}")),
+    }
+}
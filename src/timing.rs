@@ -0,0 +1,166 @@
+//! Time on task and the quiz countdown. Everything reads time through
+//! `Clock`, so the logic can run against a hand-driven clock.
+
+use std::cell::Cell;
+
+use crate::Question;
+
+/// Average or single answer time (ms) above which a fact isn't fluent yet
+pub const SLOW_MS: f64 = 6_000.0;
+
+pub trait Clock {
+    /// Milliseconds since some fixed point
+    fn now_ms(&self) -> f64;
+}
+
+/// The browser's wall clock
+pub struct BrowserClock;
+
+impl Clock for BrowserClock {
+    fn now_ms(&self) -> f64 {
+        js_sys::Date::now()
+    }
+}
+
+/// A clock that only moves when told to, for exercising timing logic
+#[derive(Default)]
+pub struct ManualClock {
+    now: Cell<f64>,
+}
+
+impl ManualClock {
+    pub fn advance(&self, ms: f64) {
+        self.now.set(self.now.get() + ms);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now_ms(&self) -> f64 {
+        self.now.get()
+    }
+}
+
+/// Open a focus interval on a question (no-op if one is already open)
+pub fn start_timing(q: &mut Question, clock: &dyn Clock) {
+    if q.focus_started.is_none() {
+        q.focus_started = Some(clock.now_ms());
+    }
+}
+
+/// Close an open focus interval into `elapsed_ms`
+pub fn stop_timing(q: &mut Question, clock: &dyn Clock) {
+    if let Some(start) = q.focus_started.take() {
+        q.elapsed_ms += (clock.now_ms() - start).max(0.0);
+    }
+}
+
+/// Right, but took long enough that the fact isn't automatic yet
+pub fn slow_but_correct(q: &Question) -> bool {
+    q.is_correct == Some(true) && q.elapsed_ms > SLOW_MS
+}
+
+/// Deadline for a whole-quiz countdown, `None` when untimed
+pub fn deadline(limit_secs: u32, clock: &dyn Clock) -> Option<f64> {
+    (limit_secs > 0).then(|| clock.now_ms() + limit_secs as f64 * 1000.0)
+}
+
+/// Whole seconds left before `deadline`, never negative
+pub fn seconds_left(deadline: f64, clock: &dyn Clock) -> u32 {
+    ((deadline - clock.now_ms()) / 1000.0).ceil().max(0.0) as u32
+}
+
+/// "2:05"
+pub fn format_clock(secs: u32) -> String {
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+/// "4.2s"
+pub fn format_secs(ms: f64) -> String {
+    format!("{:.1}s", ms / 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{question_from_fact, BaseOp, Fact};
+
+    fn question() -> Question {
+        question_from_fact(
+            Fact {
+                op: BaseOp::Add,
+                a: 3,
+                b: 4,
+            },
+            2,
+        )
+    }
+
+    #[test]
+    fn focus_intervals_add_up() {
+        let clock = ManualClock::default();
+        let mut q = question();
+
+        start_timing(&mut q, &clock);
+        clock.advance(1_500.0);
+        // A second focus while one is open keeps the first start
+        start_timing(&mut q, &clock);
+        clock.advance(500.0);
+        stop_timing(&mut q, &clock);
+        assert_eq!(q.elapsed_ms, 2_000.0);
+        assert!(q.focus_started.is_none());
+
+        // Time away from the box doesn't count
+        clock.advance(10_000.0);
+        stop_timing(&mut q, &clock);
+        assert_eq!(q.elapsed_ms, 2_000.0);
+
+        start_timing(&mut q, &clock);
+        clock.advance(3_000.0);
+        stop_timing(&mut q, &clock);
+        assert_eq!(q.elapsed_ms, 5_000.0);
+    }
+
+    #[test]
+    fn slow_only_when_right_and_over_the_limit() {
+        let clock = ManualClock::default();
+        let mut q = question();
+        start_timing(&mut q, &clock);
+        clock.advance(SLOW_MS + 1.0);
+        stop_timing(&mut q, &clock);
+
+        q.is_correct = Some(true);
+        assert!(slow_but_correct(&q));
+        q.is_correct = Some(false);
+        assert!(!slow_but_correct(&q));
+
+        q.is_correct = Some(true);
+        q.elapsed_ms = SLOW_MS;
+        assert!(!slow_but_correct(&q));
+    }
+
+    #[test]
+    fn countdown() {
+        let clock = ManualClock::default();
+        clock.advance(42_000.0);
+        assert_eq!(deadline(0, &clock), None);
+
+        let end = deadline(120, &clock).unwrap();
+        assert_eq!(end, 162_000.0);
+        assert_eq!(seconds_left(end, &clock), 120);
+
+        clock.advance(500.0);
+        assert_eq!(seconds_left(end, &clock), 120);
+        clock.advance(59_500.0);
+        assert_eq!(seconds_left(end, &clock), 60);
+        clock.advance(60_000.0);
+        assert_eq!(seconds_left(end, &clock), 0);
+        clock.advance(5_000.0);
+        assert_eq!(seconds_left(end, &clock), 0);
+    }
+
+    #[test]
+    fn clock_text() {
+        assert_eq!(format_clock(125), "2:05");
+        assert_eq!(format_secs(4_240.0), "4.2s");
+    }
+}
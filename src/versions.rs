@@ -0,0 +1,163 @@
+//! Several versions of one worksheet (A, B, C, ...) so neighbours can't
+//! copy. Every version follows the same question plan, and each fact is
+//! matched to the one in the same spot of version A by how much work it
+//! takes (carries, borrows, which times table), so the versions are the
+//! same test with different numbers.
+
+use crate::{
+    draw_fact, fact_key, generate_fallback_word_problem, generate_questions_with_ai_placeholders,
+    op_symbol, question_from_fact, tier_level, with_seed, BaseOp, Fact, Question, QuizConfig,
+};
+
+pub const MAX_VERSIONS: u8 = 6;
+
+/// Candidate facts tried per question while looking for an equal match
+const MATCH_DRAWS: usize = 200;
+
+/// "Version A", "Version B", ...
+pub fn version_label(index: usize) -> String {
+    format!("Version {}", (b'A' + index as u8) as char)
+}
+
+fn digits(n: i32) -> u8 {
+    n.unsigned_abs().to_string().len() as u8
+}
+
+/// Carries (or borrows) when working column by column
+fn regroupings(fact: &Fact) -> u8 {
+    let (mut a, mut b) = (fact.a, fact.b);
+    let mut carry = 0;
+    let mut count = 0;
+    while a > 0 || b > 0 {
+        let (da, db) = (a % 10, b % 10);
+        carry = match fact.op {
+            BaseOp::Sub => (da - carry < db) as i32,
+            _ => (da + db + carry >= 10) as i32,
+        };
+        count += carry as u8;
+        a /= 10;
+        b /= 10;
+    }
+    count
+}
+
+/// 0–2, 3–5, 6–9 and 10+ are the usual steps up in the times tables
+fn table_band(n: i32) -> u8 {
+    match n {
+        ..=2 => 0,
+        3..=5 => 1,
+        6..=9 => 2,
+        _ => 3,
+    }
+}
+
+/// Rough amount of work a fact takes; equal effort means equally hard
+fn effort(fact: &Fact) -> (u8, u8) {
+    match fact.op {
+        BaseOp::Add | BaseOp::Sub => (digits(fact.a.max(fact.b)), regroupings(fact)),
+        BaseOp::Mul => {
+            let (small, big) = (fact.a.min(fact.b), fact.a.max(fact.b));
+            (table_band(small), table_band(big))
+        }
+        BaseOp::Div => {
+            let quotient = if fact.b == 0 { 0 } else { fact.a / fact.b };
+            let (small, big) = (fact.b.min(quotient), fact.b.max(quotient));
+            (table_band(small), table_band(big))
+        }
+    }
+}
+
+fn effort_gap(a: (u8, u8), b: (u8, u8)) -> u8 {
+    a.0.abs_diff(b.0) + a.1.abs_diff(b.1)
+}
+
+/// Word problems come from the offline template so every version is ready to print
+fn local_word_problem(cfg: &QuizConfig, template: &Question) -> Question {
+    let (prompt, answer, kind) = generate_fallback_word_problem(cfg);
+    Question {
+        prompt,
+        answer,
+        kind,
+        ..template.clone()
+    }
+}
+
+/// A fact for the same spot as `target`: same operation, same effort when
+/// one turns up, and different numbers whenever the range allows
+fn matching_fact(
+    cfg: &QuizConfig,
+    level: u8,
+    target: &Fact,
+    used: &[(u8, i32, i32)],
+) -> Result<Fact, String> {
+    let want = effort(target);
+    let target_key = fact_key(target, cfg.commuted_equal);
+    let mut best: Option<(u8, Fact)> = None;
+    for _ in 0..MATCH_DRAWS {
+        let Some(fact) = draw_fact(cfg, level, target.op, used) else {
+            break;
+        };
+        // The same fact only if nothing else fits
+        let gap = effort_gap(effort(&fact), want)
+            + if fact_key(&fact, cfg.commuted_equal) == target_key {
+                10
+            } else {
+                0
+            };
+        if gap == 0 {
+            return Ok(fact);
+        }
+        if best.map_or(true, |(g, _)| gap < g) {
+            best = Some((gap, fact));
+        }
+    }
+    best.map(|(_, fact)| fact).ok_or_else(|| {
+        format!(
+            "Ran out of different {} facts for this many versions. \
+             Ask for fewer questions or versions, or allow repeats.",
+            op_symbol(target.op)
+        )
+    })
+}
+
+/// `count` versions of a worksheet for `cfg`, version A first
+pub fn generate_versions(
+    cfg: &QuizConfig,
+    count: usize,
+    seed: u64,
+) -> Result<Vec<Vec<Question>>, String> {
+    let level = tier_level(&cfg.difficulty);
+    let first = with_seed(seed, || {
+        generate_questions_with_ai_placeholders(cfg).map(|qs| {
+            qs.iter()
+                .map(|q| match q.fact {
+                    Some(_) => q.clone(),
+                    None => local_word_problem(cfg, q),
+                })
+                .collect::<Vec<_>>()
+        })
+    })?;
+
+    let mut versions = vec![first];
+    for v in 1..count {
+        let version_seed = seed.wrapping_add((v as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+        let version = with_seed(version_seed, || {
+            let mut used = Vec::new();
+            let mut qs = Vec::with_capacity(versions[0].len());
+            for q in &versions[0] {
+                let question = match &q.fact {
+                    Some(target) => {
+                        let fact = matching_fact(cfg, level, target, &used)?;
+                        used.push(fact_key(&fact, cfg.commuted_equal));
+                        question_from_fact(fact, level)
+                    }
+                    None => local_word_problem(cfg, q),
+                };
+                qs.push(question);
+            }
+            Ok::<_, String>(qs)
+        })?;
+        versions.push(version);
+    }
+    Ok(versions)
+}
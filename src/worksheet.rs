@@ -0,0 +1,271 @@
+//! Paper worksheet: what "Print Quiz" actually prints. A header for name,
+//! date and class, the questions in columns with room to work, and the
+//! answer key on a page of its own. Hidden on screen.
+
+use serde::{Deserialize, Serialize};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::qr::QrView;
+use crate::storage::{app_key, load_json, save_json};
+use crate::versions::MAX_VERSIONS;
+use crate::Question;
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum WorkSpace {
+    None,
+    Some,
+    Lots,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorksheetSettings {
+    pub title: String,
+    pub instructions: String,
+    /// Pre-filled on the "Class" line; blank leaves a line to write on
+    pub class_name: String,
+    /// 1..=4
+    pub columns: u8,
+    pub work_space: WorkSpace,
+    pub answer_key: bool,
+    /// Print the share-link QR code in the header when there is one
+    pub include_qr: bool,
+    /// How many versions "Print versions" makes
+    pub versions: u8,
+}
+
+impl Default for WorksheetSettings {
+    fn default() -> Self {
+        WorksheetSettings {
+            title: "Math Quest Worksheet".to_string(),
+            instructions: "Show your work. Write each answer on the line.".to_string(),
+            class_name: String::new(),
+            columns: 2,
+            work_space: WorkSpace::Some,
+            answer_key: true,
+            include_qr: true,
+            versions: 3,
+        }
+    }
+}
+
+pub fn load_settings() -> WorksheetSettings {
+    load_json(&app_key("worksheet")).unwrap_or_default()
+}
+
+pub fn save_settings(settings: &WorksheetSettings) {
+    save_json(&app_key("worksheet"), settings);
+}
+
+/// "7 × 8 = ?" reads as "7 × 8 =" on paper, with the answer line after it
+fn paper_prompt(q: &Question) -> String {
+    q.prompt.trim_end_matches('?').trim_end().to_string()
+}
+
+#[derive(Properties, PartialEq)]
+pub struct WorksheetProps {
+    pub questions: Vec<Question>,
+    pub settings: WorksheetSettings,
+    #[prop_or_default]
+    pub link: Option<AttrValue>,
+    /// "Version B" when several versions print together
+    #[prop_or_default]
+    pub label: Option<AttrValue>,
+}
+
+#[function_component(Worksheet)]
+pub fn worksheet(props: &WorksheetProps) -> Html {
+    let s = &props.settings;
+    let columns = format!(
+        "grid-template-columns: repeat({}, 1fr);",
+        s.columns.clamp(1, 4)
+    );
+    let work_class = match s.work_space {
+        WorkSpace::None => "ws-work-none",
+        WorkSpace::Some => "ws-work-some",
+        WorkSpace::Lots => "ws-work-lots",
+    };
+    let title = match &props.label {
+        Some(label) => format!("{} – {}", s.title, label),
+        None => s.title.clone(),
+    };
+
+    html! {
+        <div class="ws-sheet">
+            <div class="ws-header">
+                <div class="ws-header-text">
+                    <div class="ws-title">{title.clone()}</div>
+                    <div class="ws-fields">
+                        <span>{"Name: ____________________"}</span>
+                        <span>{"Date: ____________"}</span>
+                        if s.class_name.trim().is_empty() {
+                            <span>{"Class: ____________"}</span>
+                        } else {
+                            <span>{format!("Class: {}", s.class_name.trim())}</span>
+                        }
+                    </div>
+                    if !s.instructions.trim().is_empty() {
+                        <div class="ws-instructions">{s.instructions.clone()}</div>
+                    }
+                </div>
+                if let (true, Some(link)) = (s.include_qr, props.link.clone()) {
+                    <QrView text={link} class={classes!("ws-qr")} />
+                }
+            </div>
+            <div class={classes!("ws-questions", work_class)} style={columns}>
+                { for props.questions.iter().enumerate().map(|(i, q)| html! {
+                    <div class="ws-question">
+                        <div class="ws-number">{format!("{}.", i + 1)}</div>
+                        <div class="ws-prompt">
+                            {paper_prompt(q)}
+                            <span class="ws-answer-line">{"________"}</span>
+                        </div>
+                        <div class="ws-work"></div>
+                    </div>
+                }) }
+            </div>
+
+            if s.answer_key {
+                <div class="ws-key">
+                    <div class="ws-title">{format!("Answer key – {}", title)}</div>
+                    <ol class="ws-key-list">
+                        { for props.questions.iter().map(|q| html! {
+                            <li>
+                                <span class="ws-key-prompt">{paper_prompt(q)}</span>
+                                {" "}
+                                <strong>{q.answer}</strong>
+                            </li>
+                        }) }
+                    </ol>
+                </div>
+            }
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct WorksheetSettingsFormProps {
+    pub settings: WorksheetSettings,
+    pub on_change: Callback<WorksheetSettings>,
+}
+
+#[function_component(WorksheetSettingsForm)]
+pub fn worksheet_settings_form(props: &WorksheetSettingsFormProps) -> Html {
+    // Hand up a copy of the settings with one field changed
+    let edit = |apply: fn(&mut WorksheetSettings, String)| {
+        let settings = props.settings.clone();
+        let on_change = props.on_change.clone();
+        move |value: String| {
+            let mut s = settings.clone();
+            apply(&mut s, value);
+            on_change.emit(s);
+        }
+    };
+    let text = |apply: fn(&mut WorksheetSettings, String)| {
+        let edit = edit(apply);
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            edit(input.value());
+        })
+    };
+    let choice = |apply: fn(&mut WorksheetSettings, String)| {
+        let edit = edit(apply);
+        Callback::from(move |e: Event| {
+            let select: HtmlInputElement = e.target_unchecked_into();
+            edit(select.value());
+        })
+    };
+    let flag = |apply: fn(&mut WorksheetSettings, String)| {
+        let edit = edit(apply);
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            edit(input.checked().to_string());
+        })
+    };
+
+    let s = &props.settings;
+    html! {
+        <div class="config-grid">
+            <div>
+                <div class="field-label"><span>{"Title"}</span></div>
+                <input
+                    class="field-input"
+                    type="text"
+                    value={s.title.clone()}
+                    oninput={text(|s, v| s.title = v)}
+                />
+                <div class="field-label"><span>{"Instructions line"}</span></div>
+                <input
+                    class="field-input"
+                    type="text"
+                    value={s.instructions.clone()}
+                    oninput={text(|s, v| s.instructions = v)}
+                />
+                <div class="field-label">
+                    <span>{"Class"}</span>
+                    <span class="field-hint">{"leave blank for a line"}</span>
+                </div>
+                <input
+                    class="field-input"
+                    type="text"
+                    value={s.class_name.clone()}
+                    oninput={text(|s, v| s.class_name = v)}
+                />
+            </div>
+            <div>
+                <div class="field-label"><span>{"Columns"}</span></div>
+                <select class="field-input" onchange={choice(|s, v| s.columns = v.parse().unwrap_or(2))}>
+                    { for (1..=4u8).map(|n| html! {
+                        <option value={n.to_string()} selected={s.columns == n}>{n}</option>
+                    }) }
+                </select>
+                <div class="field-label">
+                    <span>{"Versions"}</span>
+                    <span class="field-hint">{"for “Print versions”"}</span>
+                </div>
+                <select
+                    class="field-input"
+                    onchange={choice(|s, v| s.versions = v.parse().unwrap_or(3))}
+                >
+                    { for (2..=MAX_VERSIONS).map(|n| html! {
+                        <option value={n.to_string()} selected={s.versions == n}>
+                            {format!("{n} (A–{})", (b'A' + n - 1) as char)}
+                        </option>
+                    }) }
+                </select>
+                <div class="field-label"><span>{"Space to show work"}</span></div>
+                <select
+                    class="field-input"
+                    onchange={choice(|s, v| {
+                        s.work_space = match v.as_str() {
+                            "none" => WorkSpace::None,
+                            "lots" => WorkSpace::Lots,
+                            _ => WorkSpace::Some,
+                        }
+                    })}
+                >
+                    <option value="none" selected={s.work_space == WorkSpace::None}>{"None"}</option>
+                    <option value="some" selected={s.work_space == WorkSpace::Some}>{"Some"}</option>
+                    <option value="lots" selected={s.work_space == WorkSpace::Lots}>{"Lots"}</option>
+                </select>
+                <div class="checkbox-row">
+                    <input
+                        type="checkbox"
+                        checked={s.answer_key}
+                        oninput={flag(|s, v| s.answer_key = v == "true")}
+                    />
+                    <span>{"Answer key on its own page"}</span>
+                </div>
+                <div class="checkbox-row">
+                    <input
+                        type="checkbox"
+                        checked={s.include_qr}
+                        oninput={flag(|s, v| s.include_qr = v == "true")}
+                    />
+                    <span>{"QR code of the share link (after “Share Link”)"}</span>
+                </div>
+            </div>
+        </div>
+    }
+}